@@ -0,0 +1,27 @@
+use pdfplot::{linspace, Plot, Recording};
+use std::time::Instant;
+
+fn main() {
+    let x = linspace(0.0, 10.0, 200);
+    let plots = 1000;
+
+    let start = Instant::now();
+    for i in 0..plots {
+        let y = x
+            .iter()
+            .map(|x| (x * (1.0 + i as f64 / 100.0)).sin())
+            .collect::<Vec<_>>();
+        let mut plot = Plot::new();
+        plot.xlabel("time (s)")
+            .ylabel("amplitude")
+            .plot(&x, &y)
+            .render_to(&mut Recording::new());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{} plots in {:.1?}, {:.1?} per plot",
+        plots,
+        elapsed,
+        elapsed / plots
+    );
+}
@@ -1,9 +1,7 @@
-use pdfplot::Plot;
+use pdfplot::{linspace, Plot};
 
 fn main() {
-    let x = (0..4096)
-        .map(|i| i as f64 / 4096. * 590. + 10.)
-        .collect::<Vec<_>>();
+    let x = linspace(10., 600., 4096);
     let y = x
         .iter()
         .map(|x| (-(x - 300.0).powi(2) / 1200.0).exp() * 0.06)
@@ -0,0 +1,297 @@
+use pdfpdf::{Alignment, Color, Font, Matrix, Pdf, Point, Size};
+
+/// The drawing operations that a plot is rendered with, in PDF-style coordinates: points, with
+/// the origin at the bottom left of the page
+///
+/// `Plot::write_to` renders into a `pdfpdf::Pdf` and `Plot::write_svg_to` with an SVG writer;
+/// anything else that implements this can be drawn into with `Plot::render_to`. Every method
+/// returns the backend so that calls can be chained.
+pub trait Backend {
+    /// Start a new page; a plot draws onto exactly one
+    fn add_page(&mut self, size: Size<f64>) -> &mut Self;
+
+    fn font(&mut self, font: Font, size: f64) -> &mut Self;
+
+    /// Set the color of both strokes and fills
+    fn set_color(&mut self, color: Color) -> &mut Self;
+
+    fn set_line_width(&mut self, width: f64) -> &mut Self;
+
+    /// Clip everything drawn afterwards on this page to a rectangle
+    fn set_clipping_box(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self;
+
+    /// Stroke the outline of a rectangle
+    fn draw_rectangle(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self;
+
+    fn move_to(&mut self, point: Point<f64>) -> &mut Self;
+
+    fn line_to(&mut self, point: Point<f64>) -> &mut Self;
+
+    /// Stroke the path built up by `move_to` and `line_to`
+    fn end_line(&mut self) -> &mut Self;
+
+    /// Stroke a line through the points
+    fn draw_line<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>;
+
+    /// Fill the inside of a closed polygon, by the even-odd rule
+    fn fill_polygon<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>;
+
+    fn draw_text(&mut self, position: Point<f64>, alignment: Alignment, text: &str) -> &mut Self;
+
+    /// Draw text rotated counterclockwise by `degrees` about `position`
+    fn draw_rotated_text(
+        &mut self,
+        position: Point<f64>,
+        alignment: Alignment,
+        degrees: i32,
+        text: &str,
+    ) -> &mut Self;
+
+    /// Move everything drawn afterwards on this page
+    fn translate(&mut self, x: f64, y: f64) -> &mut Self;
+
+    /// Stretch RGB pixels over a rectangle, with the first row of pixels at the bottom
+    fn draw_image(
+        &mut self,
+        rgb: &[u8],
+        width: usize,
+        height: usize,
+        origin: Point<f64>,
+        size: Size<f64>,
+    ) -> &mut Self;
+}
+
+// Rows of a scanline fill are this far apart, in points. Each row is stroked half again as wide
+// so that viewers don't show seams between neighboring rows, and ends flat at the polygon's edges
+// with PDF's default butt cap.
+const FILL_SPACING: f64 = 0.5;
+
+/// The horizontal runs `(y, left, right)` that cover a polygon by the even-odd rule, one row of
+/// them every `FILL_SPACING` points or less
+pub(crate) fn scanlines(x_values: &[f64], y_values: &[f64]) -> (f64, Vec<(f64, f64, f64)>) {
+    let points = x_values
+        .iter()
+        .cloned()
+        .zip(y_values.iter().cloned())
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .collect::<Vec<_>>();
+    let bottom = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let top = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    if points.len() < 3 || bottom >= top {
+        return (0.0, Vec::new());
+    }
+    let rows = ((top - bottom) / FILL_SPACING).ceil() as usize;
+    let spacing = (top - bottom) / rows as f64;
+
+    let mut runs = Vec::new();
+    let mut crossings = Vec::new();
+    for row in 0..rows {
+        let y = bottom + (row as f64 + 0.5) * spacing;
+        crossings.clear();
+        for (i, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(i + 1) % points.len()];
+            // An edge includes its lower end but not its upper one, so a row through a vertex
+            // counts it once
+            if (y0 <= y) != (y1 <= y) {
+                crossings.push(x0 + (y - y0) * (x1 - x0) / (y1 - y0));
+            }
+        }
+        crossings.sort_by(f64::total_cmp);
+        runs.extend(crossings.chunks_exact(2).map(|pair| (y, pair[0], pair[1])));
+    }
+    (spacing, runs)
+}
+
+/// A `pdfpdf::Pdf` that fills by stroking, since pdfpdf only has stroking operators
+pub(crate) struct PdfCanvas {
+    pub(crate) pdf: Pdf,
+    // Restored after a fill, which strokes at its own width
+    line_width: f64,
+}
+
+impl PdfCanvas {
+    pub(crate) fn new(pdf: Pdf) -> Self {
+        Self {
+            pdf,
+            line_width: 1.0,
+        }
+    }
+}
+
+impl Backend for PdfCanvas {
+    fn add_page(&mut self, size: Size<f64>) -> &mut Self {
+        self.pdf.add_page(size);
+        self
+    }
+
+    fn font(&mut self, font: Font, size: f64) -> &mut Self {
+        self.pdf.font(font, size);
+        self
+    }
+
+    fn set_color(&mut self, color: Color) -> &mut Self {
+        self.pdf.set_color(color);
+        self
+    }
+
+    fn set_line_width(&mut self, width: f64) -> &mut Self {
+        self.line_width = width;
+        self.pdf.set_line_width(width);
+        self
+    }
+
+    fn set_clipping_box(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        self.pdf.set_clipping_box(origin, size);
+        self
+    }
+
+    fn draw_rectangle(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        self.pdf.draw_rectangle(origin, size);
+        self
+    }
+
+    fn move_to(&mut self, point: Point<f64>) -> &mut Self {
+        self.pdf.move_to(point);
+        self
+    }
+
+    fn line_to(&mut self, point: Point<f64>) -> &mut Self {
+        self.pdf.line_to(point);
+        self
+    }
+
+    fn end_line(&mut self) -> &mut Self {
+        self.pdf.end_line();
+        self
+    }
+
+    fn draw_line<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        self.pdf.draw_line(x_values, y_values);
+        self
+    }
+
+    fn fill_polygon<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        let x_values = x_values.into_iter().collect::<Vec<_>>();
+        let y_values = y_values.into_iter().collect::<Vec<_>>();
+        let (spacing, runs) = scanlines(&x_values, &y_values);
+        if runs.is_empty() {
+            return self;
+        }
+        self.pdf.set_line_width(spacing * 1.5);
+        for (y, left, right) in runs {
+            self.pdf
+                .move_to(Point { x: left, y })
+                .line_to(Point { x: right, y })
+                .end_line();
+        }
+        self.pdf.set_line_width(self.line_width);
+        self
+    }
+
+    fn draw_text(&mut self, position: Point<f64>, alignment: Alignment, text: &str) -> &mut Self {
+        self.pdf.draw_text(position, alignment, text);
+        self
+    }
+
+    fn draw_rotated_text(
+        &mut self,
+        position: Point<f64>,
+        alignment: Alignment,
+        degrees: i32,
+        text: &str,
+    ) -> &mut Self {
+        // Text is placed in the rotated coordinates, so rotate the position the other way
+        let angle = (degrees as f64).to_radians();
+        self.pdf.transform(Matrix::rotate_deg(degrees)).draw_text(
+            Point {
+                x: position.x * angle.cos() + position.y * angle.sin(),
+                y: position.y * angle.cos() - position.x * angle.sin(),
+            },
+            alignment,
+            text,
+        );
+        self.pdf.transform(Matrix::rotate_deg(-degrees));
+        self
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> &mut Self {
+        self.pdf.transform(Matrix::translate(x, y));
+        self
+    }
+
+    fn draw_image(
+        &mut self,
+        rgb: &[u8],
+        width: usize,
+        height: usize,
+        origin: Point<f64>,
+        size: Size<f64>,
+    ) -> &mut Self {
+        // Images are drawn in a unit per pixel, so scale them up into place
+        let (x_scale, y_scale) = (size.width / width as f64, size.height / height as f64);
+        self.pdf
+            .transform(Matrix::scale(x_scale, y_scale) * Matrix::translate(origin.x, origin.y));
+        self.pdf.add_image_at(
+            pdfpdf::Image::new(rgb, width as u64, height as u64),
+            Point { x: 0, y: 0 },
+        );
+        // Undo the transform so that later drawing is in page coordinates
+        self.pdf.transform(
+            Matrix::translate(-origin.x, -origin.y) * Matrix::scale(1.0 / x_scale, 1.0 / y_scale),
+        );
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scanlines_cover_a_square() {
+        let (spacing, runs) = scanlines(&[0.0, 2.0, 2.0, 0.0], &[0.0, 0.0, 2.0, 2.0]);
+        assert_eq!(spacing, 0.5);
+        assert_eq!(
+            runs,
+            vec![
+                (0.25, 0.0, 2.0),
+                (0.75, 0.0, 2.0),
+                (1.25, 0.0, 2.0),
+                (1.75, 0.0, 2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn scanlines_follow_slanted_edges_and_holes() {
+        // A triangle narrows to its apex
+        let (_, runs) = scanlines(&[0.0, 4.0, 2.0], &[0.0, 0.0, 1.0]);
+        assert_eq!(runs, vec![(0.25, 0.5, 3.5), (0.75, 1.5, 2.5)]);
+
+        // A V shape has two runs on each row below its notch
+        let (_, runs) = scanlines(&[0.0, 1.0, 2.0, 3.0, 4.0], &[1.0, 0.0, 0.5, 0.0, 1.0]);
+        assert_eq!(runs[0], (0.25, 0.75, 1.5));
+        assert_eq!(runs[1], (0.25, 2.5, 3.25));
+        assert_eq!(runs[2], (0.75, 0.25, 3.75));
+    }
+
+    #[test]
+    fn scanlines_skip_polygons_without_area() {
+        assert!(scanlines(&[0.0, 1.0, 2.0], &[1.0, 1.0, 1.0]).1.is_empty());
+        assert!(scanlines(&[0.0, 1.0], &[0.0, 1.0]).1.is_empty());
+    }
+}
@@ -1,3 +1,39 @@
+/// A table of colors used to draw scalar data
+#[derive(Clone, Debug)]
+pub enum Colormap {
+    Viridis,
+    /// 256 RGB entries, each channel from 0 to 1
+    Custom(Box<[[f64; 3]; 256]>),
+}
+
+impl Colormap {
+    /// Build a colormap from evenly spaced RGB stops, each channel from 0 to 1, by linearly
+    /// interpolating between them to fill a 256 entry table like the built-in ones
+    pub fn from_stops(stops: &[(f64, f64, f64)]) -> Self {
+        assert!(!stops.is_empty(), "a colormap needs at least one color");
+        let mut table = Box::new([[0.0; 3]; 256]);
+        for (i, entry) in table.iter_mut().enumerate() {
+            let position = i as f64 / 255.0 * (stops.len() - 1) as f64;
+            let below = stops[position.floor() as usize];
+            let above = stops[position.ceil() as usize];
+            let fraction = position.fract();
+            *entry = [
+                below.0 + (above.0 - below.0) * fraction,
+                below.1 + (above.1 - below.1) * fraction,
+                below.2 + (above.2 - below.2) * fraction,
+            ];
+        }
+        Colormap::Custom(table)
+    }
+
+    pub(crate) fn table(&self) -> &[[f64; 3]; 256] {
+        match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Custom(table) => table,
+        }
+    }
+}
+
 pub const VIRIDIS: [[f64; 3]; 256] = [
     [0.267004, 0.004874, 0.329415],
     [0.268510, 0.009605, 0.335427],
@@ -1,35 +1,1029 @@
 mod util;
-pub use util::loadtxt;
-use util::{FloatMax, ToU64};
+pub use util::{linspace, loadtxt, loadtxt_named, logspace, savetxt, LoadError};
+use util::{percentile, FloatMax, Rng, ToU64};
 
 mod colormaps;
+pub use colormaps::Colormap;
 
-use pdfpdf::{Alignment::*, Color, Matrix, Pdf, Point, Size};
+mod backend;
+pub use backend::Backend;
+use backend::PdfCanvas;
+
+mod recording;
+pub use recording::{DrawCall, Recording};
+
+mod svg;
+use svg::Svg;
+
+pub use pdfpdf::{Alignment, Color, Font, Point, Size};
+use pdfpdf::{Alignment::*, Pdf};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 pub struct Plot {
-    pdf: Pdf,
     width: f64,
     height: f64,
     font_size: f64,
     tick_length: f64,
     x_tick_interval: Option<f64>,
     y_tick_interval: Option<f64>,
+    x_tick_format: Option<TickFormat>,
+    y_tick_format: Option<TickFormat>,
     xlim: Option<(f64, f64)>,
     ylim: Option<(f64, f64)>,
+    xlim_percentile: Option<(f64, f64)>,
+    ylim_percentile: Option<(f64, f64)>,
+    xscale: Scale,
+    yscale: Scale,
+    pad_to_next_tick: bool,
+    tick_snap: bool,
+    log_minor_labels: Option<(f64, Vec<f64>)>,
+    x_break: Option<(f64, f64)>,
+    ytick_label_width: Option<f64>,
+    drop_clipped: bool,
+    precision: Precision,
+    table: Option<Table>,
+    table_precision: usize,
+    clip_padding: f64,
+    simplify_tolerance: f64,
+    figure_padding: f64,
+    data_aspect: Option<f64>,
+    title: Option<String>,
     xlabel: Option<String>,
     ylabel: Option<String>,
+    ylabel_rotation: i32,
+    secondary_xaxis: Option<SecondaryAxis>,
+    caption: Option<String>,
+    title_bold: bool,
+    label_bold: bool,
     marker: Option<Marker>,
+    marker_filled: bool,
+    marker_size: f64,
+    marker_size_units: Units,
+    crisp_markers: bool,
     linestyle: Option<LineStyle>,
+    hatch: Option<Hatch>,
+    items: Vec<(i32, Item)>,
+    axis_breaks: Vec<(Axis2, f64)>,
+    zorder: Option<i32>,
+    threshold_color: Option<(f64, Color)>,
+    jitter: f64,
+    jitter_seed: u64,
+    rng: Rng,
+    draw_frame: bool,
+    spines_zero: bool,
+    polar: bool,
+    phase_fold: Option<(f64, bool)>,
+    arrow_spines: bool,
+    zero_line: bool,
+    start_marker: Option<(Marker, Color)>,
+    legend_entries: Vec<LegendEntry>,
+    figure: usize,
+    end_marker: Option<(Marker, Color)>,
+    interpolate_colormap: bool,
+    image_origin: Origin,
+    colormap: Colormap,
+    fit: Option<(f64, f64)>,
+    colors: Vec<Color>,
 }
 
 #[derive(Clone, Copy, Debug)]
 pub enum Marker {
     Dot,
+    Square,
+    Triangle,
+}
+
+/// How precisely `Plot::write_to` writes coordinates
+#[derive(Clone, Copy, Debug)]
+pub enum Precision {
+    /// Decimal places of a point
+    Digits(u8),
+    /// Chosen from the spacing of the plotted data on the page, between 1 and 4 places
+    Adaptive,
+}
+
+/// Which edge of an image its first row of data is drawn along
+#[derive(Clone, Copy, Debug)]
+pub enum Origin {
+    Lower,
+    Upper,
 }
 
+/// What a size is measured in
 #[derive(Clone, Copy, Debug)]
+pub enum Units {
+    /// 1/72 of an inch on the page
+    Points,
+    /// Data coordinates, so the size scales with the axes
+    Data,
+}
+
+#[derive(Clone, Debug)]
 pub enum LineStyle {
     Solid,
+    Dashed,
+    Dotted,
+    /// Alternating lengths of dash and gap, in points, starting with a dash. An empty pattern is
+    /// solid.
+    Custom(Vec<f64>),
+    /// No line at all, for a series of markers in a plot whose other series have lines
+    None,
+}
+
+impl LineStyle {
+    // The dash pattern to draw with, or `None` for a solid line
+    fn dashes(&self) -> Option<&[f64]> {
+        match self {
+            LineStyle::Solid | LineStyle::None => None,
+            LineStyle::Dashed => Some(&[6.0, 4.0]),
+            LineStyle::Dotted => Some(&[1.5, 3.0]),
+            LineStyle::Custom(pattern) if pattern.is_empty() => None,
+            LineStyle::Custom(pattern) => Some(pattern),
+        }
+    }
+
+    fn validate(&self) {
+        if let Some(pattern) = self.dashes() {
+            assert!(
+                pattern.iter().all(|&l| l.is_finite() && l >= 0.0),
+                "dash lengths must be finite and not negative, got {:?}",
+                pattern
+            );
+            assert!(
+                pattern.iter().sum::<f64>() > 0.0,
+                "a dash pattern needs some length, got {:?}",
+                pattern
+            );
+        }
+    }
+}
+
+/// Patterns that fills can be drawn with in place of a solid color, for figures that have to
+/// work in grayscale
+#[derive(Clone, Copy, Debug)]
+pub enum Hatch {
+    /// Lines rising at 45 degrees
+    Diagonal,
+    /// Lines at both 45 and -45 degrees
+    CrossHatch,
+    Horizontal,
+    Vertical,
+    Dots,
+}
+
+// A table that's drawn in place of the plot
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<f64>>,
+}
+
+// An axis that relabels the ticks of another in different units
+struct SecondaryAxis {
+    transform: Box<dyn Fn(f64) -> f64>,
+    label: String,
+}
+
+/// Standard page sizes for `Plot::page_size`
+#[derive(Clone, Copy, Debug)]
+pub enum PageSize {
+    A4Portrait,
+    A4Landscape,
+    A5Portrait,
+    A5Landscape,
+    Letter,
+    LetterLandscape,
+    /// 16:9, the size of a widescreen presentation slide
+    Slide,
+}
+
+impl PageSize {
+    // Width and height in points
+    fn points(self) -> (f64, f64) {
+        let mm = |v: f64| v * 72.0 / 25.4;
+        match self {
+            PageSize::A4Portrait => (mm(210.0), mm(297.0)),
+            PageSize::A4Landscape => (mm(297.0), mm(210.0)),
+            PageSize::A5Portrait => (mm(148.0), mm(210.0)),
+            PageSize::A5Landscape => (mm(210.0), mm(148.0)),
+            PageSize::Letter => (612.0, 792.0),
+            PageSize::LetterLandscape => (792.0, 612.0),
+            PageSize::Slide => (960.0, 540.0),
+        }
+    }
+}
+
+/// Which part of complex values `plot_complex_as` plots
+#[derive(Clone, Copy, Debug)]
+pub enum ComplexMode {
+    Magnitude,
+    /// In radians, from -π to π
+    Phase,
+    Real,
+    Imaginary,
+}
+
+/// Selects the x or y axis of a plot
+#[derive(Clone, Copy, Debug)]
+pub enum Axis2 {
+    X,
+    Y,
+}
+
+struct Series {
+    x_values: Vec<f64>,
+    y_values: Vec<f64>,
+    style: SeriesStyle,
+}
+
+// RGB pixels, placed at `extent` in data coordinates if it's known
+struct Image {
+    png_bytes: Vec<u8>,
+    width: usize,
+    height: usize,
+    extent: Option<((f64, f64), (f64, f64))>,
+}
+
+// Something to draw in the plot area, kept until the axes are known
+enum Item {
+    Series(Series),
+    Markers {
+        x_values: Vec<f64>,
+        y_values: Vec<f64>,
+        color: Color,
+    },
+    Segments {
+        segments: Vec<((f64, f64), (f64, f64))>,
+        color: Color,
+    },
+    // A line with a color for each segment
+    GradientLine {
+        x_values: Vec<f64>,
+        y_values: Vec<f64>,
+        colors: Vec<Color>,
+    },
+    // Points with a vertical bar from `lower` to `upper` through each
+    ErrorBars {
+        x_values: Vec<f64>,
+        y_values: Vec<f64>,
+        lower: Vec<f64>,
+        upper: Vec<f64>,
+        color: Color,
+    },
+    // A straight line across the whole x range
+    Line {
+        slope: f64,
+        intercept: f64,
+        color: Color,
+    },
+    Image(Image),
+    VSpans {
+        intervals: Vec<(f64, f64)>,
+        hatch: Option<Hatch>,
+    },
+    // Short ticks along the inside of the bottom or left side of the frame
+    Rug {
+        values: Vec<f64>,
+        axis: Axis2,
+    },
+    // A dashed vertical line across the whole y range, labeled at the top
+    VLine {
+        x: f64,
+        label: String,
+    },
+    // Filled polygons with their own colors, in data coordinates
+    Polygons {
+        polygons: Vec<(Vec<f64>, Vec<f64>, Color)>,
+        hatch: Option<Hatch>,
+    },
+    // A colored vertical band across the whole y range, labeled at the top
+    Highlight {
+        start: f64,
+        end: f64,
+        label: String,
+        color: Color,
+        hatch: Option<Hatch>,
+    },
+}
+
+impl Item {
+    // Reinterpret x as an angle in radians and y as a radius, for polar plots
+    fn into_cartesian(self) -> Item {
+        let convert = |theta: &[f64], r: &[f64]| -> (Vec<f64>, Vec<f64>) {
+            theta
+                .iter()
+                .zip(r)
+                .map(|(&theta, &r)| (r * theta.cos(), r * theta.sin()))
+                .unzip()
+        };
+        match self {
+            Item::Series(Series {
+                x_values,
+                y_values,
+                style,
+            }) => {
+                let (x_values, y_values) = convert(&x_values, &y_values);
+                Item::Series(Series {
+                    x_values,
+                    y_values,
+                    style,
+                })
+            }
+            Item::Markers {
+                x_values,
+                y_values,
+                color,
+            } => {
+                let (x_values, y_values) = convert(&x_values, &y_values);
+                Item::Markers {
+                    x_values,
+                    y_values,
+                    color,
+                }
+            }
+            Item::GradientLine {
+                x_values,
+                y_values,
+                colors,
+            } => {
+                let (x_values, y_values) = convert(&x_values, &y_values);
+                Item::GradientLine {
+                    x_values,
+                    y_values,
+                    colors,
+                }
+            }
+            Item::Segments { segments, color } => Item::Segments {
+                segments: segments
+                    .into_iter()
+                    .map(|((t0, r0), (t1, r1))| {
+                        (
+                            (r0 * t0.cos(), r0 * t0.sin()),
+                            (r1 * t1.cos(), r1 * t1.sin()),
+                        )
+                    })
+                    .collect(),
+                color,
+            },
+            Item::Polygons { polygons, hatch } => Item::Polygons {
+                polygons: polygons
+                    .into_iter()
+                    .map(|(theta, r, color)| {
+                        let (x, y) = convert(&theta, &r);
+                        (x, y, color)
+                    })
+                    .collect(),
+                hatch,
+            },
+            // Everything else is tied to a rectangular frame
+            item => panic!("{} can't be drawn in a polar plot", item.name()),
+        }
+    }
+
+    // What the user called to make the item, for error messages
+    fn name(&self) -> &'static str {
+        match self {
+            Item::Series(_) => "a line",
+            Item::Markers { .. } => "a set of markers",
+            Item::Segments { .. } => "a set of segments",
+            Item::GradientLine { .. } => "a gradient line",
+            Item::ErrorBars { .. } => "errorbar",
+            Item::Line { .. } => "regression",
+            Item::Image(_) => "an image",
+            Item::VSpans { .. } => "axvspans",
+            Item::Rug { .. } => "rug",
+            Item::VLine { .. } => "a vertical line",
+            Item::Polygons { .. } => "a filled region",
+            Item::Highlight { .. } => "highlight_xrange",
+        }
+    }
+
+    fn default_zorder(&self) -> i32 {
+        match self {
+            // Shading belongs behind the data
+            Item::VSpans { .. } | Item::Highlight { .. } => -1,
+            _ => 0,
+        }
+    }
+}
+
+// A key for a legend, drawn where it was asked for on the canvas
+struct LegendEntry {
+    at: Point<f64>,
+    color: Color,
+    marker: Option<Marker>,
+    linestyle: Option<LineStyle>,
+    label: String,
+}
+
+/// A series that points are still being added to, from `Plot::begin_series`
+#[derive(Debug)]
+pub struct SeriesHandle {
+    index: usize,
+    phase_fold: Option<(f64, bool)>,
+    // Whether points are angles and radii, as they were when the series was begun
+    polar: bool,
+    // Which figure the series belongs to, since `finish_figure` clears the items
+    figure: usize,
+}
+
+/// Per-series overrides for `plot_as`; any field left as `None` falls back to the color cycle
+/// and the plot-wide settings
+#[derive(Clone, Debug, Default)]
+pub struct SeriesStyle {
+    pub color: Option<Color>,
+    /// `Some(LineStyle::None)` turns the line off for this series even when the plot has one
+    pub linestyle: Option<LineStyle>,
+    pub line_width: Option<f64>,
+    pub marker: Option<Marker>,
+}
+
+/// Defaults for a plot's look, to set a house style once with `Plot::with_theme` instead of
+/// repeating the same settings for every figure
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub font_size: f64,
+    pub tick_length: f64,
+    pub title_bold: bool,
+    pub label_bold: bool,
+    /// The colors that successive series cycle through
+    pub colors: Vec<Color>,
+    pub marker_filled: bool,
+    pub spines_zero: bool,
+    pub arrow_spines: bool,
+    pub figure_padding: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font_size: 20.0,
+            tick_length: 6.0,
+            title_bold: false,
+            label_bold: false,
+            colors: COLOR_CYCLE.to_vec(),
+            marker_filled: true,
+            spines_zero: false,
+            arrow_spines: false,
+            figure_padding: 0.0,
+        }
+    }
+}
+
+impl Theme {
+    /// Open axes with arrowheads and short ticks, for conceptual figures
+    pub fn minimal() -> Self {
+        Self {
+            tick_length: 4.0,
+            arrow_spines: true,
+            ..Self::default()
+        }
+    }
+
+    /// Large bold labels that stay legible when the figure is shrunk to a column width, and a
+    /// cycle that starts with black for grayscale printing
+    pub fn paper() -> Self {
+        let mut colors = vec![Color::gray(0)];
+        colors.extend_from_slice(&COLOR_CYCLE);
+        Self {
+            font_size: 24.0,
+            title_bold: true,
+            label_bold: true,
+            colors,
+            figure_padding: 10.0,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Scale {
+    Linear,
+    /// Linear within `linthresh` of zero and logarithmic beyond it, so that data which crosses
+    /// zero can still span many orders of magnitude
+    SymLog {
+        linthresh: f64,
+    },
+}
+
+impl Scale {
+    fn validate(self) {
+        if let Scale::SymLog { linthresh } = self {
+            assert!(
+                linthresh > 0.0 && linthresh.is_finite(),
+                "SymLog's linthresh must be positive and finite, got {}",
+                linthresh
+            );
+        }
+    }
+
+    // Map a data value into the space in which the axis is linear. For SymLog the linear region
+    // maps to [-1, 1] and each decade beyond it has a width of 1.
+    fn apply(self, v: f64) -> f64 {
+        match self {
+            Scale::Linear => v,
+            Scale::SymLog { linthresh } => {
+                if v.abs() <= linthresh {
+                    v / linthresh
+                } else {
+                    v.signum() * (1.0 + (v.abs() / linthresh).log10())
+                }
+            }
+        }
+    }
+
+    fn invert(self, v: f64) -> f64 {
+        match self {
+            Scale::Linear => v,
+            Scale::SymLog { linthresh } => {
+                if v.abs() <= 1.0 {
+                    v * linthresh
+                } else {
+                    v.signum() * linthresh * 10.0f64.powf(v.abs() - 1.0)
+                }
+            }
+        }
+    }
+}
+
+const JITTER_SEED: u64 = 0x5eed;
+
+// Diameter of a marker in points
+const MARKER_SIZE: f64 = 6.0;
+
+// Length of the line drawn for a legend entry, in points
+const LEGEND_SAMPLE_LENGTH: f64 = 20.0;
+
+// Distance between the lines or dots of a hatch pattern, in points
+const HATCH_SPACING: f64 = 4.0;
+
+// matplotlib's tab10 cycle, so that successive series are easy to tell apart
+const COLOR_CYCLE: [Color; 10] = [
+    Color {
+        red: 31,
+        green: 119,
+        blue: 180,
+    },
+    Color {
+        red: 255,
+        green: 127,
+        blue: 14,
+    },
+    Color {
+        red: 44,
+        green: 160,
+        blue: 44,
+    },
+    Color {
+        red: 214,
+        green: 39,
+        blue: 40,
+    },
+    Color {
+        red: 148,
+        green: 103,
+        blue: 189,
+    },
+    Color {
+        red: 140,
+        green: 86,
+        blue: 75,
+    },
+    Color {
+        red: 227,
+        green: 119,
+        blue: 194,
+    },
+    Color {
+        red: 127,
+        green: 127,
+        blue: 127,
+    },
+    Color {
+        red: 188,
+        green: 189,
+        blue: 34,
+    },
+    Color {
+        red: 23,
+        green: 190,
+        blue: 207,
+    },
+];
+
+// Take x modulo the period and sort by it, carrying the other columns along, then append a copy
+// one period along if asked to. Points with any value that isn't finite are dropped, since a gap
+// has nowhere to go once the cycles are folded on top of each other.
+fn phase_fold(
+    x_values: &[f64],
+    columns: &[&[f64]],
+    period: f64,
+    repeat: bool,
+) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let phase = |i: usize| x_values[i].rem_euclid(period);
+    let mut order = (0..x_values.len())
+        .filter(|&i| x_values[i].is_finite() && columns.iter().all(|c| c[i].is_finite()))
+        .collect::<Vec<_>>();
+    order.sort_by(|&a, &b| phase(a).total_cmp(&phase(b)));
+
+    let copies = if repeat { 2 } else { 1 };
+    let x_values = (0..copies)
+        .flat_map(|copy| order.iter().map(move |&i| phase(i) + copy as f64 * period))
+        .collect();
+    let columns = columns
+        .iter()
+        .map(|column| {
+            (0..copies)
+                .flat_map(|_| order.iter().map(|&i| column[i]))
+                .collect()
+        })
+        .collect();
+    (x_values, columns)
+}
+
+thread_local! {
+    // A document to measure text with, and the widths it has measured keyed by the text, the
+    // font's name, and the bits of the font size. Every plot on a thread shares them, so plots
+    // made in a loop don't measure the same tick labels over again.
+    static MEASURE: RefCell<Pdf> = RefCell::new(Pdf::new());
+    static TEXT_WIDTHS: RefCell<HashMap<(String, &'static str, u64), f64>> =
+        RefCell::new(HashMap::new());
+}
+
+// The width of text in Helvetica or its bold weight at `size`
+fn text_width(text: &str, bold: bool, size: f64) -> f64 {
+    let name = if bold { "Helvetica-Bold" } else { "Helvetica" };
+    TEXT_WIDTHS.with(|widths| {
+        *widths
+            .borrow_mut()
+            .entry((text.to_string(), name, size.to_bits()))
+            .or_insert_with(|| {
+                MEASURE.with(|pdf| {
+                    pdf.borrow_mut()
+                        .font(font_weight(bold), size)
+                        .width_of(text)
+                })
+            })
+    })
+}
+
+fn font_weight(bold: bool) -> pdfpdf::Font {
+    if bold {
+        pdfpdf::Font::HelveticaBold
+    } else {
+        pdfpdf::Font::Helvetica
+    }
+}
+
+// Look up a position between 0 and 255 in a colormap
+fn sample_colormap(map: &[[f64; 3]; 256], position: f64, interpolate: bool) -> [f64; 3] {
+    let index = position as usize;
+    if !interpolate || index >= 255 {
+        return map[index];
+    }
+    let fraction = position - index as f64;
+    let (below, above) = (map[index], map[index + 1]);
+    [
+        below[0] + (above[0] - below[0]) * fraction,
+        below[1] + (above[1] - below[1]) * fraction,
+        below[2] + (above[2] - below[2]) * fraction,
+    ]
+}
+
+// Where a value falls along the colormap, from 0 to 255; a constant image has no range to divide
+// by, so it is drawn in the low color
+fn colormap_position(value: f64, (min, max): (f64, f64)) -> f64 {
+    if max > min {
+        (value - min) / (max - min) * 255.0
+    } else {
+        0.0
+    }
+}
+
+/// Split a line into the runs of points that should be drawn as connected paths
+///
+/// Lines are broken at points that aren't finite. When `bounds` is given as
+/// `((left, right), (bottom, top))`, each segment is also cut where it crosses the bounds and the
+/// parts outside are dropped.
+fn split_line(
+    points: impl Iterator<Item = (f64, f64)>,
+    bounds: Option<((f64, f64), (f64, f64))>,
+) -> Vec<Vec<(f64, f64)>> {
+    let mut runs: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut previous = None;
+    // Whether the last run ends at the previous point, so that the next segment continues it
+    let mut connected = false;
+    for point in points {
+        if !(point.0.is_finite() && point.1.is_finite()) {
+            previous = None;
+            connected = false;
+            continue;
+        }
+        if let Some(start) = previous {
+            let clipped = match bounds {
+                Some(bounds) => clip_segment(start, point, bounds),
+                None => Some((start, point)),
+            };
+            if let Some((a, b)) = clipped {
+                if !connected || a != start {
+                    runs.push(vec![a]);
+                }
+                runs.last_mut().unwrap().push(b);
+                connected = b == point;
+            } else {
+                connected = false;
+            }
+        }
+        previous = Some(point);
+    }
+    runs
+}
+
+/// Cut a line into the dashes of an on/off pattern, measured along the line and starting with a
+/// dash at its first point
+fn dash_line(points: &[(f64, f64)], pattern: &[f64]) -> Vec<Vec<(f64, f64)>> {
+    let mut dashes = Vec::new();
+    let mut current = match points.first() {
+        Some(&first) => vec![first],
+        None => return dashes,
+    };
+    // How many entries of the pattern we've passed, which repeats an odd-length pattern with
+    // the dashes and gaps swapped, and how much of the current one is left
+    let mut step = 0;
+    let mut remaining = pattern[0];
+    for pair in points.windows(2) {
+        let (mut start, end) = (pair[0], pair[1]);
+        let mut length = (end.0 - start.0).hypot(end.1 - start.1);
+        while remaining < length {
+            let t = remaining / length;
+            let split = (
+                start.0 + (end.0 - start.0) * t,
+                start.1 + (end.1 - start.1) * t,
+            );
+            // Even entries are dashes and odd ones are gaps
+            if step % 2 == 0 {
+                current.push(split);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![split];
+            }
+            length -= remaining;
+            start = split;
+            step += 1;
+            remaining = pattern[step % pattern.len()];
+        }
+        remaining -= length;
+        if step % 2 == 0 {
+            current.push(end);
+        }
+    }
+    if step % 2 == 0 && current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+// The pixels of an image in the order they're drawn, which is from the bottom row up
+fn rows_from_bottom<T>(data: &[T], width: usize, origin: Origin) -> Vec<&T> {
+    let rows = data.chunks(width.max(1));
+    match origin {
+        Origin::Lower => rows.flatten().collect(),
+        Origin::Upper => rows.rev().flatten().collect(),
+    }
+}
+
+// Most of the way to white, for fills that lines and other data should stand out over
+fn lighten(color: Color) -> Color {
+    let lighten = |c: u8| c + ((255 - c) as f64 * 0.7) as u8;
+    Color {
+        red: lighten(color.red),
+        green: lighten(color.green),
+        blue: lighten(color.blue),
+    }
+}
+
+// Points around a circle in data coordinates, with the first point repeated at the end
+fn circle_points(center: (f64, f64), radius: f64) -> Vec<(f64, f64)> {
+    (0..=128)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::PI / 64.0;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+/// Remove points from a line with the Ramer-Douglas-Peucker algorithm, keeping every point that is
+/// more than `tolerance` away from the simplified line
+fn simplify(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // Split ranges at their farthest point until every point is close enough
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let (start, end) = (points[first], points[last]);
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = dx.hypot(dy);
+        let distance = |p: (f64, f64)| {
+            if length == 0.0 {
+                (p.0 - start.0).hypot(p.1 - start.1)
+            } else {
+                (dy * (p.0 - start.0) - dx * (p.1 - start.1)).abs() / length
+            }
+        };
+        let farthest = (first + 1..last).map(|i| (i, distance(points[i]))).fold(
+            None,
+            |farthest: Option<(usize, f64)>, (i, d)| match farthest {
+                Some((_, max)) if max >= d => farthest,
+                _ => Some((i, d)),
+            },
+        );
+        if let Some((i, d)) = farthest {
+            if d > tolerance {
+                keep[i] = true;
+                ranges.push((first, i));
+                ranges.push((i, last));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(&p, _)| p)
+        .collect()
+}
+
+/// Draw a hatch pattern inside a polygon given in canvas coordinates
+///
+/// Each line of the pattern is cut where it crosses the outline and drawn only between
+/// alternate crossings, which clips it to the polygon without needing a clipping path. The lines
+/// sit on a grid fixed to the page so that neighboring regions line up.
+fn hatch_polygon<B: Backend>(canvas: &mut B, pattern: Hatch, xs: &[f64], ys: &[f64]) {
+    let vertices = xs
+        .iter()
+        .copied()
+        .zip(ys.iter().copied())
+        .collect::<Vec<_>>();
+    if vertices.len() < 3 {
+        return;
+    }
+    let edges = || {
+        vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    };
+
+    if let Hatch::Dots = pattern {
+        // A dot wherever a grid point lands inside by the even-odd rule
+        let inside = |x: f64, y: f64| {
+            edges()
+                .filter(|&((x0, y0), (x1, y1))| {
+                    (y0 > y) != (y1 > y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0)
+                })
+                .count()
+                % 2
+                == 1
+        };
+        let (xmin, xmax) = (
+            xs.iter().copied().fold(f64::INFINITY, f64::min),
+            xs.iter().copied().float_max(),
+        );
+        let (ymin, ymax) = (
+            ys.iter().copied().fold(f64::INFINITY, f64::min),
+            ys.iter().copied().float_max(),
+        );
+        let radius = HATCH_SPACING / 6.0;
+        let mut y = (ymin / HATCH_SPACING).ceil() * HATCH_SPACING;
+        while y <= ymax {
+            let mut x = (xmin / HATCH_SPACING).ceil() * HATCH_SPACING;
+            while x <= xmax {
+                if inside(x, y) {
+                    canvas.fill_polygon(
+                        (0..8).map(|i| x + radius * (i as f64 * 45.0).to_radians().cos()),
+                        (0..8).map(|i| y + radius * (i as f64 * 45.0).to_radians().sin()),
+                    );
+                }
+                x += HATCH_SPACING;
+            }
+            y += HATCH_SPACING;
+        }
+        return;
+    }
+
+    let directions: &[(f64, f64)] = match pattern {
+        Hatch::Diagonal => &[(1.0, 1.0)],
+        Hatch::CrossHatch => &[(1.0, 1.0), (1.0, -1.0)],
+        Hatch::Horizontal => &[(1.0, 0.0)],
+        Hatch::Vertical => &[(0.0, 1.0)],
+        Hatch::Dots => unreachable!(),
+    };
+    for &(dx, dy) in directions {
+        let length = dx.hypot(dy);
+        let (dx, dy) = (dx / length, dy / length);
+        // Each line is the set of points at some offset along the normal
+        let (nx, ny) = (-dy, dx);
+        let offsets = vertices
+            .iter()
+            .map(|&(x, y)| x * nx + y * ny)
+            .collect::<Vec<_>>();
+        let last = offsets.iter().copied().float_max();
+        let mut offset = (offsets.iter().copied().fold(f64::INFINITY, f64::min) / HATCH_SPACING)
+            .ceil()
+            * HATCH_SPACING;
+        while offset <= last {
+            let mut crossings = edges()
+                .filter_map(|((x0, y0), (x1, y1))| {
+                    let d0 = x0 * nx + y0 * ny - offset;
+                    let d1 = x1 * nx + y1 * ny - offset;
+                    if (d0 < 0.0) == (d1 < 0.0) {
+                        return None;
+                    }
+                    let t = d0 / (d0 - d1);
+                    Some((x0 + t * (x1 - x0)) * dx + (y0 + t * (y1 - y0)) * dy)
+                })
+                .collect::<Vec<_>>();
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            for pair in crossings.chunks_exact(2) {
+                canvas.draw_line(
+                    pair.iter().map(|&along| along * dx + offset * nx),
+                    pair.iter().map(|&along| along * dy + offset * ny),
+                );
+            }
+            offset += HATCH_SPACING;
+        }
+    }
+}
+
+// Fill a polygon in canvas coordinates with the current color, or with a pattern in it
+fn fill_region<B: Backend>(canvas: &mut B, hatch: Option<Hatch>, xs: Vec<f64>, ys: Vec<f64>) {
+    match hatch {
+        Some(pattern) => hatch_polygon(canvas, pattern, &xs, &ys),
+        None => {
+            canvas.fill_polygon(xs, ys);
+        }
+    }
+}
+
+/// Clip a segment to a box with the Liang-Barsky algorithm, returning the part inside
+fn clip_segment(
+    start: (f64, f64),
+    end: (f64, f64),
+    ((left, right), (bottom, top)): ((f64, f64), (f64, f64)),
+) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for &(p, q) in &[
+        (-dx, start.0 - left),
+        (dx, right - start.0),
+        (-dy, start.1 - bottom),
+        (dy, top - start.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    // Ends that weren't moved are returned exactly, so that callers can tell
+    let a = if t0 > 0.0 {
+        (start.0 + t0 * dx, start.1 + t0 * dy)
+    } else {
+        start
+    };
+    let b = if t1 < 1.0 {
+        (start.0 + t1 * dx, start.1 + t1 * dy)
+    } else {
+        end
+    };
+    Some((a, b))
+}
+
+/// Format a value that doesn't sit on a round tick, with at most 3 decimal places
+fn short_number(v: f64) -> String {
+    if v != 0.0 && (v.abs() < 1e-3 || v.abs() >= 1e5) {
+        format!("{:.2e}", v)
+    } else {
+        let text = format!("{:.3}", v);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
 }
 
 fn compute_tick_interval(range: f64) -> f64 {
@@ -59,27 +1053,167 @@ fn compute_tick_interval(range: f64) -> f64 {
     possible_tick_intervals[chosen_index]
 }
 
-struct Axis {
+/// A tick label pattern with one number in it, parsed from a string like `"{:.1f} Hz"`
+struct TickFormat {
+    prefix: String,
+    precision: Option<usize>,
+    exponent: bool,
+    suffix: String,
+}
+
+impl TickFormat {
+    fn parse(pattern: &str) -> Self {
+        let mut prefix = String::new();
+        let mut spec = None;
+        let mut suffix = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            let text = if spec.is_none() {
+                &mut prefix
+            } else {
+                &mut suffix
+            };
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    assert!(
+                        spec.is_none(),
+                        "tick format {:?} has more than one placeholder",
+                        pattern
+                    );
+                    let mut inside = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inside.push(c),
+                            None => panic!("tick format {:?} has an unclosed {{", pattern),
+                        }
+                    }
+                    spec = Some(inside);
+                }
+                '}' => panic!("tick format {:?} has an unmatched }}", pattern),
+                c => text.push(c),
+            }
+        }
+
+        let spec = spec.unwrap_or_else(|| panic!("tick format {:?} has no placeholder", pattern));
+        let spec = spec.strip_prefix(':').unwrap_or(&spec);
+        let (spec, exponent) = match spec.strip_suffix('e') {
+            Some(spec) => (spec, true),
+            None => (spec.strip_suffix('f').unwrap_or(spec), false),
+        };
+        let precision = match spec {
+            "" => None,
+            _ => Some(
+                spec.strip_prefix('.')
+                    .and_then(|digits| digits.parse().ok())
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "tick format {:?} has a placeholder that isn't {{}}, {{:e}}, {{:.N}}, \
+                             {{:.Nf}}, or {{:.Ne}}",
+                            pattern
+                        )
+                    }),
+            ),
+        };
+
+        Self {
+            prefix,
+            precision,
+            exponent,
+            suffix,
+        }
+    }
+
+    fn format(&self, v: f64) -> String {
+        let number = match (self.precision, self.exponent) {
+            (Some(precision), true) => format!("{:.*e}", precision, v),
+            (Some(precision), false) => format!("{:.*}", precision, v),
+            (None, true) => format!("{:e}", v),
+            (None, false) => format!("{}", v),
+        };
+        format!("{}{}{}", self.prefix, number, self.suffix)
+    }
+}
+
+/// The limits and ticks chosen for one axis of a plot
+#[derive(Clone, Debug)]
+pub struct Axis {
     limits: (f64, f64),
+    scale: Scale,
     tick_interval: f64,
-    num_ticks: u64,
+    ticks: Vec<f64>,
     tick_labels: Vec<String>,
     margin: f64,
+    break_range: Option<(f64, f64)>,
 }
 
 impl Axis {
-    fn tick_labels(&mut self) {
+    pub fn limits(&self) -> (f64, f64) {
+        self.limits
+    }
+
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    pub fn tick_interval(&self) -> f64 {
+        self.tick_interval
+    }
+
+    /// Tick positions in data coordinates
+    pub fn ticks(&self) -> &[f64] {
+        &self.ticks
+    }
+
+    pub fn tick_labels(&self) -> &[String] {
+        &self.tick_labels
+    }
+
+    // Where a value sits along the axis before it's stretched onto the page, with the broken out
+    // range collapsed to a single point
+    fn position(&self, v: f64) -> f64 {
+        let v = match self.break_range {
+            Some((from, to)) if v >= to => v - (to - from),
+            Some((from, _)) if v > from => from,
+            _ => v,
+        };
+        self.scale.apply(v)
+    }
+
+    fn format_tick_labels(&mut self) {
         let tick_precision = self.tick_interval.abs().log10();
         let tick_max = self.limits.0.abs().max(self.limits.1.abs()).log10();
+        let scale = self.scale;
+        // Counts and indices read better without a decimal point
+        let whole =
+            self.tick_interval.fract() == 0.0 && self.ticks.iter().all(|v| v.fract() == 0.0);
 
-        self.tick_labels = (0..self.num_ticks)
-            .map(|i| i as f64 * self.tick_interval + self.limits.0)
-            .map(|v| {
-                if v == 0.0 {
+        self.tick_labels = self
+            .ticks
+            .iter()
+            .map(|&v| {
+                if let Scale::SymLog { .. } = scale {
+                    // Ticks are at powers of 10, so only the exponent needs to be legible
+                    if v == 0.0 || (v.abs() >= 1e-3 && v.abs() < 1e5) {
+                        format!("{}", v)
+                    } else {
+                        format!("{:e}", v)
+                    }
+                } else if v == 0.0 {
                     format!("{}", v)
                 } else if tick_precision < 0.0 {
                     // If we have small ticks, format so that the last sig fig is visible
                     format!("{:.*}", tick_precision.abs().ceil() as usize, v)
+                } else if whole && tick_max < 4. {
+                    format!("{:.0}", v)
                 } else if tick_max < 4. {
                     // For numbers close to +/- 1, use default formatting
                     format!("{:.2}", v)
@@ -95,24 +1229,108 @@ impl Axis {
     }
 }
 
+/// The mapping from data to canvas coordinates, and the size of the page it's on
+struct Frame {
+    xaxis: Axis,
+    yaxis: Axis,
+    plot_width: f64,
+    plot_height: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    fn to_canvas_x(&self, x: f64) -> f64 {
+        let axis = &self.xaxis;
+        let (start, end) = (axis.position(axis.limits.0), axis.position(axis.limits.1));
+        let x_scale = self.plot_width / (end - start);
+        ((axis.position(x) - start) * x_scale) + self.yaxis.margin
+    }
+
+    fn to_canvas_y(&self, y: f64) -> f64 {
+        let axis = &self.yaxis;
+        let (start, end) = (axis.position(axis.limits.0), axis.position(axis.limits.1));
+        let y_scale = self.plot_height / (end - start);
+        ((axis.position(y) - start) * y_scale) + self.xaxis.margin
+    }
+}
+
 impl Plot {
     pub fn new() -> Self {
-        let mut pdf = Pdf::new();
-        pdf.font(pdfpdf::Font::Helvetica, 20.0).precision(4);
+        Self::with_theme(Theme::default())
+    }
+
+    /// Start a plot with a house style, which the individual settings can still override
+    pub fn with_theme(theme: Theme) -> Self {
+        assert!(
+            !theme.colors.is_empty(),
+            "a theme needs at least one color to cycle through"
+        );
         Self {
-            pdf,
-            font_size: 20.0,
+            font_size: theme.font_size,
             width: 810.0,
             height: 630.0,
-            tick_length: 6.0,
+            tick_length: theme.tick_length,
             x_tick_interval: None,
             y_tick_interval: None,
+            x_tick_format: None,
+            y_tick_format: None,
             xlim: None,
             ylim: None,
+            xlim_percentile: None,
+            ylim_percentile: None,
+            xscale: Scale::Linear,
+            yscale: Scale::Linear,
+            pad_to_next_tick: false,
+            tick_snap: true,
+            log_minor_labels: None,
+            x_break: None,
+            ytick_label_width: None,
+            drop_clipped: false,
+            precision: Precision::Digits(4),
+            table: None,
+            table_precision: 2,
+            clip_padding: 2.0,
+            simplify_tolerance: 0.0,
+            figure_padding: theme.figure_padding,
+            data_aspect: None,
+            title: None,
             xlabel: None,
             ylabel: None,
+            ylabel_rotation: 90,
+            secondary_xaxis: None,
+            caption: None,
+            title_bold: theme.title_bold,
+            label_bold: theme.label_bold,
             marker: None,
+            marker_filled: theme.marker_filled,
+            marker_size: MARKER_SIZE,
+            marker_size_units: Units::Points,
+            crisp_markers: false,
             linestyle: Some(LineStyle::Solid),
+            hatch: None,
+            items: Vec::new(),
+            axis_breaks: Vec::new(),
+            zorder: None,
+            threshold_color: None,
+            jitter: 0.0,
+            jitter_seed: JITTER_SEED,
+            rng: Rng::new(JITTER_SEED),
+            draw_frame: true,
+            spines_zero: theme.spines_zero,
+            polar: false,
+            phase_fold: None,
+            arrow_spines: theme.arrow_spines,
+            zero_line: false,
+            start_marker: None,
+            legend_entries: Vec::new(),
+            figure: 0,
+            end_marker: None,
+            interpolate_colormap: false,
+            image_origin: Origin::Lower,
+            colormap: Colormap::Viridis,
+            fit: None,
+            colors: theme.colors,
         }
     }
 
@@ -126,6 +1344,188 @@ impl Plot {
         self
     }
 
+    pub fn title(&mut self, text: &str) -> &mut Self {
+        self.title = Some(text.to_string());
+        self
+    }
+
+    /// Autoscale the x axis to the `lo`th through `hi`th percentile of the data, so that a few
+    /// outliers don't dominate the plot. Points outside the limits are clipped.
+    pub fn xlim_percentile(&mut self, lo: f64, hi: f64) -> &mut Self {
+        self.xlim_percentile = Some((lo, hi));
+        self
+    }
+
+    /// Autoscale the y axis to the `lo`th through `hi`th percentile of the data, so that a few
+    /// outliers don't dominate the plot. Points outside the limits are clipped.
+    pub fn ylim_percentile(&mut self, lo: f64, hi: f64) -> &mut Self {
+        self.ylim_percentile = Some((lo, hi));
+        self
+    }
+
+    pub fn xscale(&mut self, scale: Scale) -> &mut Self {
+        scale.validate();
+        self.xscale = scale;
+        self
+    }
+
+    pub fn yscale(&mut self, scale: Scale) -> &mut Self {
+        scale.validate();
+        self.yscale = scale;
+        self
+    }
+
+    /// On a `SymLog` axis that spans fewer than `max_decades` decades, also put labeled ticks at
+    /// each of `multiples` times every decade, such as `&[2.0, 5.0]` for 20 and 50 between 10
+    /// and 100, so that a short range isn't left with only one or two ticks
+    pub fn log_minor_labels(&mut self, max_decades: f64, multiples: &[f64]) -> &mut Self {
+        assert!(
+            multiples.iter().all(|&m| m > 1.0 && m < 10.0),
+            "multiples must be between 1 and 10, got {:?}",
+            multiples
+        );
+        self.log_minor_labels = Some((max_decades, multiples.to_vec()));
+        self
+    }
+
+    /// Whether autoscaled limits are widened out to the next tick (the default), or sit exactly at
+    /// the extremes of the data with the ticks inside them. `SymLog` axes always snap to decades.
+    pub fn tick_snap(&mut self, on: bool) -> &mut Self {
+        self.tick_snap = on;
+        self
+    }
+
+    /// When the largest data value lands exactly on a tick, extend the autoscaled limit by one
+    /// more tick so that the data doesn't sit on the frame
+    pub fn pad_to_next_tick(&mut self, on: bool) -> &mut Self {
+        self.pad_to_next_tick = on;
+        self
+    }
+
+    /// Cut lines off at the edge of the plot area instead of drawing all of them under a clipping
+    /// box, so that data far outside the limits doesn't end up in the file
+    pub fn drop_clipped(&mut self, on: bool) -> &mut Self {
+        self.drop_clipped = on;
+        self
+    }
+
+    /// Drop points from series lines as long as the line stays within `points` of where it was,
+    /// measured on the page; 0 turns this off
+    ///
+    /// Lines are simplified after they're split at missing values and, with `drop_clipped`, cut
+    /// at the edge of the plot, so each visible piece keeps its ends. Markers are unaffected.
+    ///
+    /// There's no decimation to a fixed number of points to combine this with: simplification
+    /// is the only thinning a line gets, and it works on the page rather than on point counts,
+    /// so a line keeps every corner that's farther than `points` from its neighbors however
+    /// many points that leaves.
+    pub fn simplify_tolerance(&mut self, points: f64) -> &mut Self {
+        assert!(
+            points >= 0.0,
+            "simplify tolerance can't be negative, got {}",
+            points
+        );
+        self.simplify_tolerance = points;
+        self
+    }
+
+    /// Reserve `width` points for the y tick labels instead of the width of the widest one, so
+    /// that plots made with the same setting line up their left edges
+    pub fn ytick_label_width(&mut self, width: f64) -> &mut Self {
+        assert!(
+            width >= 0.0,
+            "tick label width can't be negative, got {}",
+            width
+        );
+        self.ytick_label_width = Some(width);
+        self
+    }
+
+    /// Set the size of the page in points, 1/72 of an inch. The default is 810 by 630.
+    pub fn figsize(&mut self, width: f64, height: f64) -> &mut Self {
+        assert!(
+            width > 0.0 && height > 0.0,
+            "the page must have a positive size, got {} by {}",
+            width,
+            height
+        );
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set the size of the page in millimeters; A4 is 210 by 297
+    pub fn figsize_mm(&mut self, width: f64, height: f64) -> &mut Self {
+        self.figsize(width * 72.0 / 25.4, height * 72.0 / 25.4)
+    }
+
+    /// Set the size of the page in inches; US Letter is 8.5 by 11
+    pub fn figsize_in(&mut self, width: f64, height: f64) -> &mut Self {
+        self.figsize(width * 72.0, height * 72.0)
+    }
+
+    /// Set the size of the page to a standard paper or slide size
+    pub fn page_size(&mut self, size: PageSize) -> &mut Self {
+        let (width, height) = size.points();
+        self.figsize(width, height)
+    }
+
+    /// How many decimal places coordinates are written to PDFs with: a fixed number, 4 by
+    /// default, or as few as keep the closest neighboring points of the data apart
+    pub fn precision(&mut self, precision: Precision) -> &mut Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Let data reach `points` past the border before it's clipped, so that lines and markers
+    /// along the limits stay visible. The default is 2.
+    pub fn clip_padding(&mut self, points: f64) -> &mut Self {
+        assert!(
+            points >= 0.0,
+            "clip padding can't be negative, got {}",
+            points
+        );
+        self.clip_padding = points;
+        self
+    }
+
+    /// Leave `pad` points of blank space between everything that's drawn and the edges of the page
+    pub fn figure_padding(&mut self, pad: f64) -> &mut Self {
+        assert!(pad >= 0.0, "figure padding can't be negative, got {}", pad);
+        self.figure_padding = pad;
+        self
+    }
+
+    /// Make one unit of y data `ratio` times as long on the page as one unit of x data, shrinking
+    /// the plot area in one direction to fit
+    ///
+    /// This overrides the square plot area that images otherwise get.
+    pub fn data_aspect(&mut self, ratio: f64) -> &mut Self {
+        assert!(
+            ratio > 0.0 && ratio.is_finite(),
+            "data aspect must be positive and finite, got {}",
+            ratio
+        );
+        self.data_aspect = Some(ratio);
+        self
+    }
+
+    /// Collapse the x range from `from` to `to` out of the plot, to skip an empty stretch between
+    /// clusters of data, and mark the spot with a break glyph
+    ///
+    /// The limits and ticks are still chosen over the whole range, minus any ticks inside it or at
+    /// `to`, which would be drawn on top of one at `from`.
+    pub fn break_x(&mut self, from: f64, to: f64) -> &mut Self {
+        assert!(
+            from < to,
+            "an x break must go from a smaller to a larger value, got {} to {}",
+            from,
+            to
+        );
+        self.x_break = Some((from, to));
+        self
+    }
+
     pub fn xlabel(&mut self, text: &str) -> &mut Self {
         self.xlabel = Some(text.to_string());
         self
@@ -136,6 +1536,172 @@ impl Plot {
         self
     }
 
+    /// Rotate the y label counterclockwise by `degrees`; the default is 90, reading upwards, and 0
+    /// draws it horizontally. The left margin grows to fit.
+    pub fn ylabel_rotation(&mut self, degrees: i32) -> &mut Self {
+        self.ylabel_rotation = degrees;
+        self
+    }
+
+    /// Draw a second x axis along the top of the plot, with a tick above each bottom tick labeled
+    /// with `transform` of its value, and `label` above those
+    pub fn secondary_xaxis(
+        &mut self,
+        transform: impl Fn(f64) -> f64 + 'static,
+        label: &str,
+    ) -> &mut Self {
+        self.secondary_xaxis = Some(SecondaryAxis {
+            transform: Box::new(transform),
+            label: label.to_string(),
+        });
+        self
+    }
+
+    /// Draw small left-aligned text at the bottom of the page, below the x label, wrapped at
+    /// spaces to fit the page width
+    pub fn caption(&mut self, text: &str) -> &mut Self {
+        self.caption = Some(text.to_string());
+        self
+    }
+
+    /// Draw the title in bold; tick labels are always regular weight
+    pub fn title_bold(&mut self, on: bool) -> &mut Self {
+        self.title_bold = on;
+        self
+    }
+
+    /// Draw the axis labels in bold; tick labels are always regular weight
+    pub fn label_bold(&mut self, on: bool) -> &mut Self {
+        self.label_bold = on;
+        self
+    }
+
+    /// Draw markers whose y value is above `threshold` in the `above` color instead of the color
+    /// of their series. Lines keep the series color.
+    pub fn threshold_color(&mut self, threshold: f64, above: Color) -> &mut Self {
+        self.threshold_color = Some((threshold, above));
+        self
+    }
+
+    /// Shift each marker horizontally by a random amount of up to `amount` in data units in
+    /// either direction, so that markers which share an x value spread out into a cloud
+    ///
+    /// This only applies to series drawn without a line; markers on lines, error bars, graphs,
+    /// and regressions stay where their data puts them.
+    pub fn jitter(&mut self, amount: f64) -> &mut Self {
+        self.jitter = amount;
+        self
+    }
+
+    /// Draw the random offsets used by `jitter` starting from `seed`. Without this a fixed seed is
+    /// used, so the same plot always comes out the same.
+    pub fn jitter_seed(&mut self, seed: u64) -> &mut Self {
+        self.jitter_seed = seed;
+        self
+    }
+
+    /// Draw the border, ticks, and labels; when off, an image fills the whole page by itself
+    ///
+    /// Without a frame there are no ticks to snap to, so unset limits are exactly the extent of
+    /// the data.
+    pub fn frame(&mut self, on: bool) -> &mut Self {
+        self.draw_frame = on;
+        self
+    }
+
+    /// Treat x as an angle in radians and y as a distance from the center for lines, markers,
+    /// segments, and fills added after this, and draw circles and spokes with the angles labeled
+    /// in degrees instead of a rectangular frame
+    ///
+    /// `y_tick_interval` sets the spacing of the circles. Error bars, regressions, images, spans,
+    /// highlights, rugs, and vertical lines only make sense in a rectangular frame, so adding one
+    /// to a polar plot panics. `unit_circle` and `bullseye` stay in x and y.
+    pub fn polar(&mut self) -> &mut Self {
+        assert!(
+            self.phase_fold.is_none(),
+            "polar plots already wrap their angles, so they can't be phase folded"
+        );
+        self.polar = true;
+        self
+    }
+
+    /// Wrap the x values of data added after this into `[0, period)`, in order of phase. With
+    /// `repeat`, a second copy shifted by `period` is drawn after the first so that anything near
+    /// the wrap can be seen whole.
+    ///
+    /// This applies to lines and scatter points from `plot` and its variants, `begin_series`,
+    /// `plot_band`, and `fill_threshold`. Points that aren't finite are dropped rather than
+    /// leaving gaps. Graphs, regressions, error bars, and bars keep their x values.
+    pub fn phase_fold(&mut self, period: f64, repeat: bool) -> &mut Self {
+        assert!(
+            period > 0.0 && period.is_finite(),
+            "the period must be positive and finite, not {}",
+            period
+        );
+        assert!(!self.polar, "polar plots already wrap their angles");
+        self.phase_fold = Some((period, repeat));
+        self
+    }
+
+    /// Draw the x and y axis lines, with their ticks, through zero instead of along the edges of
+    /// the plot. An axis whose range doesn't include zero stays at the edge.
+    pub fn spines_zero(&mut self) -> &mut Self {
+        self.spines_zero = true;
+        self
+    }
+
+    /// Draw only the left and bottom axis lines, as arrows pointing towards larger values, instead
+    /// of a border around the plot
+    pub fn arrow_spines(&mut self, on: bool) -> &mut Self {
+        self.arrow_spines = on;
+        self
+    }
+
+    /// Draw a thin gray line across the plot at y = 0, behind the data, when zero is inside the y
+    /// limits
+    pub fn zero_line(&mut self, on: bool) -> &mut Self {
+        self.zero_line = on;
+        self
+    }
+
+    /// Mark where each line series starts and ends, such as to show which way a trajectory goes
+    pub fn endpoints(
+        &mut self,
+        start: Option<(Marker, Color)>,
+        end: Option<(Marker, Color)>,
+    ) -> &mut Self {
+        self.start_marker = start;
+        self.end_marker = end;
+        self
+    }
+
+    /// Whether the first row of data passed to `image` and `image_indexed` is drawn at the
+    /// bottom, which is the default, or at the top like most image files
+    pub fn image_origin(&mut self, origin: Origin) -> &mut Self {
+        self.image_origin = origin;
+        self
+    }
+
+    /// Blend between the two nearest colormap entries instead of truncating to one of them,
+    /// which removes banding from smooth gradients
+    pub fn colormap_interpolation(&mut self, on: bool) -> &mut Self {
+        self.interpolate_colormap = on;
+        self
+    }
+
+    /// Color images and other colormapped data with a gradient through `stops`, which are evenly
+    /// spaced RGB colors with each channel from 0 to 1, instead of viridis
+    pub fn colormap_custom(&mut self, stops: &[(f64, f64, f64)]) -> &mut Self {
+        self.colormap = Colormap::from_stops(stops);
+        self
+    }
+
+    /// The color that images use for a value `t` of the way from their minimum to their maximum,
+    /// with `t` clamped to between 0 and 1
+    pub fn colormap_color(&self, t: f64) -> Color {
+        self.colorize(t, (0.0, 1.0))
+    }
+
     pub fn tick_length(&mut self, length: f64) -> &mut Self {
         self.tick_length = length;
         self
@@ -151,19 +1717,91 @@ impl Plot {
         self
     }
 
+    /// Label the x ticks with a pattern like `"{:.1f} Hz"`, where the placeholder can be `{}`,
+    /// `{:e}`, or give a precision as `{:.2}`, `{:.2f}`, or `{:.2e}`. Braces are escaped by
+    /// doubling them.
+    ///
+    /// Panics if the pattern doesn't have exactly one valid placeholder.
+    pub fn x_tick_format_str(&mut self, pattern: &str) -> &mut Self {
+        self.x_tick_format = Some(TickFormat::parse(pattern));
+        self
+    }
+
+    /// Label the y ticks with a pattern; see `x_tick_format_str`
+    pub fn y_tick_format_str(&mut self, pattern: &str) -> &mut Self {
+        self.y_tick_format = Some(TickFormat::parse(pattern));
+        self
+    }
+
     pub fn marker(&mut self, marker: Option<Marker>) -> &mut Self {
         self.marker = marker;
         self
     }
 
+    /// Fill markers in, or draw only their outlines when off, which reads better where they
+    /// overlap. Markers are filled by default.
+    pub fn marker_filled(&mut self, on: bool) -> &mut Self {
+        self.marker_filled = on;
+        self
+    }
+
+    /// Set how wide markers are, in points or in data units depending on `marker_size_units`.
+    /// The default is 6 points.
+    pub fn marker_size(&mut self, size: f64) -> &mut Self {
+        assert!(
+            size > 0.0 && size.is_finite(),
+            "marker size must be positive, got {}",
+            size
+        );
+        self.marker_size = size;
+        self
+    }
+
+    /// Measure `marker_size` in points, so markers stay the same size on the page, or in data
+    /// units, so that a marker covers a fixed extent of the data along each axis
+    pub fn marker_size_units(&mut self, units: Units) -> &mut Self {
+        self.marker_size_units = units;
+        self
+    }
+
+    /// Snap the center of each marker to the middle of a point on the page, which makes small
+    /// markers look more even next to each other at the cost of moving each by up to half a point
+    pub fn crisp_markers(&mut self, on: bool) -> &mut Self {
+        self.crisp_markers = on;
+        self
+    }
+
     pub fn linestyle(&mut self, style: Option<LineStyle>) -> &mut Self {
+        if let Some(ref style) = style {
+            style.validate();
+        }
         self.linestyle = style;
         self
     }
 
-    fn digest_tick_settings(&self, x_values: &[f64], y_values: &[f64]) -> (Axis, Axis) {
+    /// Fill bars, bands, spans, and other regions added after this with a pattern in their color
+    /// instead of solid color. Lines and dots are `HATCH_SPACING` (4 points) apart on the page,
+    /// whatever the axis limits.
+    ///
+    /// `hexbin` cells and `bullseye_shaded` rings are always solid, since their tones carry the
+    /// information.
+    pub fn hatch(&mut self, pattern: Option<Hatch>) -> &mut Self {
+        self.hatch = pattern;
+        self
+    }
+
+    fn digest_tick_settings(&self, series: &[(&[f64], &[f64])]) -> (Axis, Axis) {
+        self.digest_limits(self.xlim, self.ylim, series)
+    }
+
+    fn digest_limits(
+        &self,
+        xlim: Option<(f64, f64)>,
+        ylim: Option<(f64, f64)>,
+        series: &[(&[f64], &[f64])],
+    ) -> (Axis, Axis) {
         // Pick the axes limits
-        let (min, max) = {
+        let (mut min, mut max) = {
             use std::f64;
             let mut max = Point {
                 x: f64::NEG_INFINITY,
@@ -173,335 +1811,3669 @@ impl Plot {
                 x: f64::INFINITY,
                 y: f64::INFINITY,
             };
-            for (&x, &y) in x_values.iter().zip(y_values.iter()) {
-                max.x = max.x.max(x);
-                max.y = max.y.max(y);
-                min.x = min.x.min(x);
-                min.y = min.y.min(y);
+            let (mut full_min, mut full_max) = (min, max);
+            // When the other axis is zoomed in, only the data inside the window needs to fit
+            let inside = |limits: Option<(f64, f64)>, v: f64| {
+                limits.map_or(true, |(a, b)| a.min(b) <= v && v <= a.max(b))
+            };
+            for (x_values, y_values) in series {
+                // A single NaN or infinity would otherwise take over the limits
+                for (&x, &y) in x_values
+                    .iter()
+                    .zip(y_values.iter())
+                    .filter(|(x, y)| x.is_finite() && y.is_finite())
+                {
+                    full_max = Point {
+                        x: full_max.x.max(x),
+                        y: full_max.y.max(y),
+                    };
+                    full_min = Point {
+                        x: full_min.x.min(x),
+                        y: full_min.y.min(y),
+                    };
+                    if inside(ylim, y) {
+                        max.x = max.x.max(x);
+                        min.x = min.x.min(x);
+                    }
+                    if inside(xlim, x) {
+                        max.y = max.y.max(y);
+                        min.y = min.y.min(y);
+                    }
+                }
+            }
+            // A window with no data in it still gets limits from all of it
+            if !min.x.is_finite() {
+                min.x = full_min.x;
+                max.x = full_max.x;
+            }
+            if !min.y.is_finite() {
+                min.y = full_min.y;
+                max.y = full_max.y;
             }
             (min, max)
         };
 
-        // Must either provide data or configure
-        assert!((min.x.is_finite() && max.x.is_finite()) || self.xlim.is_some());
-        assert!((min.y.is_finite() && max.y.is_finite()) || self.ylim.is_some());
-
-        // Compute the tick interval from maxes first so we can choose limits that are a multiple
-        // of the tick interval
-        let x_tick_interval = self
-            .x_tick_interval
-            .unwrap_or_else(|| compute_tick_interval(max.x - min.x));
-
-        let y_tick_interval = self
-            .y_tick_interval
-            .unwrap_or_else(|| compute_tick_interval(max.y - min.y));
-
-        let xlim = self.xlim.unwrap_or_else(|| {
-            let min_in_ticks = (min.x / x_tick_interval).floor();
-            let xmin = min_in_ticks * x_tick_interval;
-            let max_in_ticks = (max.x / x_tick_interval).ceil();
-            let xmax = max_in_ticks * x_tick_interval;
-            (xmin, xmax)
-        });
-
-        let ylim = self.ylim.unwrap_or_else(|| {
-            let min_in_ticks = (min.y / y_tick_interval).floor();
-            let ymin = min_in_ticks * y_tick_interval;
-            let max_in_ticks = (max.y / y_tick_interval).ceil();
-            let ymax = max_in_ticks * y_tick_interval;
-            (ymin, ymax)
-        });
-
-        // Compute the tick interval again but this time based on the now-known axes limits
-        // This fixes our selection of tick interval in situations where we were told odd axes
-        // limits
-        let x_tick_interval = self
-            .x_tick_interval
-            .unwrap_or_else(|| compute_tick_interval(xlim.1 - xlim.0));
+        if let Some((lo, hi)) = self.xlim_percentile {
+            let x_values = series
+                .iter()
+                .flat_map(|(x, _)| x.iter().cloned())
+                .collect::<Vec<_>>();
+            if let (Some(lo), Some(hi)) = (percentile(&x_values, lo), percentile(&x_values, hi)) {
+                min.x = lo;
+                max.x = hi;
+            }
+        }
 
-        let y_tick_interval = self
-            .y_tick_interval
-            .unwrap_or_else(|| compute_tick_interval(ylim.1 - ylim.0));
+        if let Some((lo, hi)) = self.ylim_percentile {
+            let y_values = series
+                .iter()
+                .flat_map(|(_, y)| y.iter().cloned())
+                .collect::<Vec<_>>();
+            if let (Some(lo), Some(hi)) = (percentile(&y_values, lo), percentile(&y_values, hi)) {
+                min.y = lo;
+                max.y = hi;
+            }
+        }
 
-        let x_num_ticks = ((xlim.1 - xlim.0).abs() / x_tick_interval).to_u64() + 1;
-        let y_num_ticks = ((ylim.1 - ylim.0).abs() / y_tick_interval).to_u64() + 1;
+        // Must either provide data or configure
+        assert!((min.x.is_finite() && max.x.is_finite()) || xlim.is_some());
+        assert!((min.y.is_finite() && max.y.is_finite()) || ylim.is_some());
 
-        // Quantize the tick interval so that it fits nicely
-        let x_tick_interval = x_tick_interval * (xlim.1 - xlim.0).signum();
-        let y_tick_interval = y_tick_interval * (ylim.1 - ylim.0).signum();
+        // A single point, or data that never changes, has no range to put ticks across
+        for (lo, hi) in [(&mut min.x, &mut max.x), (&mut min.y, &mut max.y)] {
+            if lo == hi {
+                let pad = if *lo == 0.0 { 1.0 } else { lo.abs() / 10.0 };
+                *lo -= pad;
+                *hi += pad;
+            }
+        }
 
-        let mut xaxis = Axis {
-            limits: xlim,
-            num_ticks: x_num_ticks,
-            tick_interval: x_tick_interval,
-            margin: 0.0,
-            tick_labels: Vec::new(),
-        };
-        xaxis.tick_labels();
+        let mut xaxis = self.digest_axis(xlim, self.x_tick_interval, self.xscale, (min.x, max.x));
+        if let Some(ref format) = self.x_tick_format {
+            xaxis.tick_labels = xaxis.ticks.iter().map(|&v| format.format(v)).collect();
+        }
+        if let Some((from, to)) = self.x_break {
+            // Ticks inside the break would all land on top of each other, and one at `to` lands on
+            // one at `from`, so keep only the ticks up to and including `from` and past `to`
+            let (ticks, tick_labels) = xaxis
+                .ticks
+                .iter()
+                .cloned()
+                .zip(xaxis.tick_labels.drain(..))
+                .filter(|&(x, _)| x <= from || x > to)
+                .unzip();
+            xaxis.ticks = ticks;
+            xaxis.tick_labels = tick_labels;
+            xaxis.break_range = Some((from, to));
+        }
 
         // X border size is 1.5 * height of the axis label label, height of the tick labels, and the tick length
         xaxis.margin = (self.font_size * 1.5) + self.font_size + self.tick_length + self.font_size;
+        // The caption is wrapped to the full page here, and rewrapped if the layout narrows it
+        xaxis.margin += self.caption_height(self.width - 2.0 * self.figure_padding);
 
-        let mut yaxis = Axis {
-            limits: ylim,
-            num_ticks: y_num_ticks,
-            tick_interval: y_tick_interval,
-            margin: 0.0,
-            tick_labels: Vec::new(),
-        };
-        yaxis.tick_labels();
+        let mut yaxis = self.digest_axis(ylim, self.y_tick_interval, self.yscale, (min.y, max.y));
+        if let Some(ref format) = self.y_tick_format {
+            yaxis.tick_labels = yaxis.ticks.iter().map(|&v| format.format(v)).collect();
+        }
 
         // Y Border size is height of the font, max width of a label, and the tick length
-        yaxis.margin = self.font_size * 2.
-            + yaxis
+        let label_width = self.ytick_label_width.unwrap_or_else(|| {
+            yaxis
                 .tick_labels
                 .iter()
-                .map(|label| self.pdf.width_of(&label))
+                .map(|label| self.width_of(label))
                 .float_max()
-            + self.tick_length
-            + self.font_size;
+        });
+        yaxis.margin = self.font_size * 2. + label_width + self.tick_length + self.font_size;
+        yaxis.margin += self.ylabel_extent() - self.font_size;
 
         (xaxis, yaxis)
     }
 
-    fn draw_axes(
-        &mut self,
-        xaxis: &Axis,
-        yaxis: &Axis,
-        to_canvas_x: impl Fn(f64) -> f64,
-        to_canvas_y: impl Fn(f64) -> f64,
-    ) {
-        // Draw the plot's border at the margins
-        self.pdf
-            .add_page(Size {
-                width: self.width,
-                height: self.height,
-            })
-            .set_color(Color::gray(0))
-            .set_line_width(1.0)
-            .draw_rectangle(
-                Point {
-                    x: to_canvas_x(xaxis.limits.0),
-                    y: to_canvas_y(yaxis.limits.0),
-                },
-                Size {
-                    width: to_canvas_x(xaxis.limits.1) - to_canvas_x(xaxis.limits.0),
-                    height: to_canvas_y(yaxis.limits.1) - to_canvas_y(yaxis.limits.0),
-                },
-            );
-
-        // Draw the x tick marks
-        for (i, label) in (0..xaxis.num_ticks).zip(&xaxis.tick_labels) {
-            let x = i as f64 * xaxis.tick_interval + xaxis.limits.0;
-            self.pdf
-                .move_to(Point {
-                    x: to_canvas_x(x),
-                    y: to_canvas_y(yaxis.limits.0),
-                })
-                .line_to(Point {
-                    x: to_canvas_x(x),
-                    y: to_canvas_y(yaxis.limits.0) - self.tick_length,
-                })
-                .end_line();
-            self.pdf.draw_text(
-                Point {
-                    x: to_canvas_x(x),
-                    y: to_canvas_y(yaxis.limits.0) - self.tick_length,
-                },
-                TopCenter,
-                label,
-            );
+    // How far the y label reaches across the page, which is one font height when it's upright
+    fn ylabel_extent(&self) -> f64 {
+        match self.ylabel {
+            Some(ref ylabel) if self.ylabel_rotation != 90 => {
+                let angle = (self.ylabel_rotation as f64).to_radians();
+                (self.width_in(ylabel, self.label_bold) * angle.cos()).abs()
+                    + (self.font_size * angle.sin()).abs()
+            }
+            _ => self.font_size,
         }
+    }
 
-        // Draw the y tick marks
-        for (i, label) in (0..yaxis.num_ticks).zip(&yaxis.tick_labels) {
-            let y = i as f64 * yaxis.tick_interval + yaxis.limits.0;
-            self.pdf
-                .move_to(Point {
-                    x: to_canvas_x(xaxis.limits.0),
-                    y: to_canvas_y(y),
-                })
-                .line_to(Point {
-                    x: to_canvas_x(xaxis.limits.0) - self.tick_length,
-                    y: to_canvas_y(y),
-                })
-                .end_line();
-            self.pdf.draw_text(
-                Point {
-                    x: to_canvas_x(xaxis.limits.0) - self.tick_length - 2.0,
-                    y: to_canvas_y(y),
-                },
-                CenterRight,
-                label,
-            );
-        }
+    // The width of text in the main font
+    fn width_of(&self, text: &str) -> f64 {
+        self.width_in(text, false)
+    }
 
-        // Draw the x label
-        if let Some(ref xlabel) = self.xlabel {
-            self.pdf.draw_text(
-                Point {
-                    x: to_canvas_x(xaxis.limits.0 + (xaxis.limits.1 - xaxis.limits.0) / 2.0),
-                    y: 4.0 + self.font_size / 2.0,
-                },
-                BottomCenter,
-                xlabel,
-            );
-        }
+    // The width of text in the main font or its bold weight
+    fn width_in(&self, text: &str, bold: bool) -> f64 {
+        text_width(text, bold, self.font_size)
+    }
 
-        // Draw the y label
-        if let Some(ref ylabel) = self.ylabel {
-            self.pdf.transform(Matrix::rotate_deg(90)).draw_text(
-                Point {
-                    x: to_canvas_y(yaxis.limits.0 + (yaxis.limits.1 - yaxis.limits.0) / 2.0),
-                    y: -6.0,
-                },
-                TopCenter,
-                ylabel,
-            );
-            self.pdf.transform(Matrix::rotate_deg(-90));
-        }
+    fn caption_font_size(&self) -> f64 {
+        self.font_size * 0.6
     }
 
-    pub fn plot(&mut self, x_values: &[f64], y_values: &[f64]) -> &mut Self {
-        let (xaxis, yaxis) = self.digest_tick_settings(x_values, y_values);
+    // Break the caption into lines at spaces so that each fits across a page of `width` inside
+    // the padding
+    fn caption_lines(&self, width: f64) -> Vec<String> {
+        let caption = match self.caption {
+            Some(ref caption) => caption,
+            None => return Vec::new(),
+        };
+        // Text is measured in the main font, and widths scale with the font size
+        let width_of = |text: &str| self.width_of(text) * self.caption_font_size() / self.font_size;
+        let max_width = width - self.font_size;
 
-        let width = self.width;
-        let height = self.height;
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in caption.split_whitespace() {
+            if !line.is_empty() && width_of(&format!("{} {}", line, word)) > max_width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
 
-        let plot_width =
-            width - yaxis.margin - self.pdf.width_of(xaxis.tick_labels.last().unwrap());
-        let plot_height = height - xaxis.margin - self.font_size;
+    // Space reserved below the x label for the caption
+    fn caption_height(&self, width: f64) -> f64 {
+        let lines = self.caption_lines(width).len();
+        if lines == 0 {
+            0.0
+        } else {
+            (lines as f64 + 0.5) * self.caption_font_size() * 1.2
+        }
+    }
+
+    // Space above the plot area, which grows to fit a title
+    fn top_margin(&self) -> f64 {
+        let title = if self.title.is_some() {
+            self.font_size * 2.5
+        } else {
+            self.font_size
+        };
+        title + self.top_labels_height()
+    }
+
+    // Space for what's drawn along the top of the plot area, below the title: the ticks, tick
+    // labels, and label of the secondary x axis, or the angle at the top of a polar plot
+    fn top_labels_height(&self) -> f64 {
+        if self.secondary_xaxis.is_some() {
+            self.tick_length + self.font_size + self.font_size * 1.5
+        } else if self.polar {
+            self.tick_length + self.font_size
+        } else {
+            0.0
+        }
+    }
+
+    // Choose the limits and ticks for one axis from its settings and the range of its data
+    fn digest_axis(
+        &self,
+        limits: Option<(f64, f64)>,
+        tick_interval: Option<f64>,
+        scale: Scale,
+        (min, max): (f64, f64),
+    ) -> Axis {
+        if let Scale::SymLog { linthresh } = scale {
+            // Snap the limits outward to the nearest decade, then put a tick on every decade
+            let limits = limits.unwrap_or_else(|| {
+                if self.draw_frame {
+                    (
+                        scale.invert(scale.apply(min).floor()),
+                        scale.invert(scale.apply(max).ceil()),
+                    )
+                } else {
+                    (min, max)
+                }
+            });
+            let (start, end) = (scale.apply(limits.0), scale.apply(limits.1));
+            let (low, high) = (start.min(end), start.max(end));
+            let mut ticks = ((low.ceil() as i64)..=(high.floor() as i64))
+                .map(|t| scale.invert(t as f64))
+                .collect::<Vec<_>>();
+            match self.log_minor_labels {
+                Some((max_decades, ref multiples)) if high - low < max_decades => {
+                    // Multiples of the decade that starts at each tick away from zero, which
+                    // begin at the edge of the linear region
+                    for t in (low.floor() as i64)..=(high.ceil() as i64) {
+                        if t == 0 {
+                            continue;
+                        }
+                        let decade = scale.invert(t as f64);
+                        for m in multiples {
+                            let v = decade * m;
+                            if low <= scale.apply(v) && scale.apply(v) <= high {
+                                ticks.push(v);
+                            }
+                        }
+                    }
+                    ticks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                }
+                _ => {}
+            }
+            if start > end {
+                ticks.reverse();
+            }
+            let mut axis = Axis {
+                limits,
+                scale,
+                tick_interval: linthresh,
+                ticks,
+                tick_labels: Vec::new(),
+                margin: 0.0,
+                break_range: None,
+            };
+            axis.format_tick_labels();
+            return axis;
+        }
+
+        // Compute the tick interval from maxes first so we can choose limits that are a multiple
+        // of the tick interval
+        let first_tick_interval = tick_interval.unwrap_or_else(|| compute_tick_interval(max - min));
+
+        // Data that's all the same value still needs snapping to get a range
+        let raw_limits = if (!self.tick_snap || !self.draw_frame) && min < max {
+            Some((min, max))
+        } else {
+            None
+        };
+        let limits = limits.or(raw_limits).unwrap_or_else(|| {
+            let min_in_ticks = (min / first_tick_interval).floor();
+            let mut max_in_ticks = (max / first_tick_interval).ceil();
+            if self.pad_to_next_tick
+                && (max_in_ticks * first_tick_interval - max).abs() < first_tick_interval * 1e-9
+            {
+                max_in_ticks += 1.0;
+            }
+            (
+                min_in_ticks * first_tick_interval,
+                max_in_ticks * first_tick_interval,
+            )
+        });
 
-        // Function to convert from plot pixels to canvas pixels
-        let to_canvas_x = |x| {
-            let x_scale = plot_width / (xaxis.limits.1 - xaxis.limits.0);
-            ((x - xaxis.limits.0) * x_scale) + yaxis.margin
+        // Compute the tick interval again but this time based on the now-known axes limits
+        // This fixes our selection of tick interval in situations where we were told odd axes
+        // limits
+        let tick_interval =
+            tick_interval.unwrap_or_else(|| compute_tick_interval(limits.1 - limits.0));
+
+        // Quantize the tick interval so that it fits nicely
+        let tick_interval = tick_interval * (limits.1 - limits.0).signum();
+
+        // Put ticks on multiples of the interval, which the limits only are if we chose them
+        let first_tick = ((limits.0 / tick_interval) - 1e-9).ceil() * tick_interval;
+        let span = (limits.1 - first_tick) / tick_interval + 1e-9;
+        let num_ticks = if span < 0.0 { 0 } else { span.to_u64() + 1 };
+
+        let mut axis = Axis {
+            limits,
+            scale,
+            tick_interval,
+            ticks: (0..num_ticks)
+                .map(|i| i as f64 * tick_interval + first_tick)
+                .collect(),
+            tick_labels: Vec::new(),
+            margin: 0.0,
+            break_range: None,
+        };
+        axis.format_tick_labels();
+        axis
+    }
+
+    // Stroke a line with a filled arrowhead at its end
+    fn draw_arrow<B: Backend>(&self, canvas: &mut B, from: Point<f64>, to: Point<f64>) {
+        let length = self.tick_length * 2.0;
+        let angle = (to.y - from.y).atan2(to.x - from.x);
+        // The line stops inside the arrowhead so that it doesn't poke out of the point
+        let base = Point {
+            x: to.x - length * angle.cos(),
+            y: to.y - length * angle.sin(),
+        };
+        let half_width = length * 0.4;
+        canvas
+            .move_to(from)
+            .line_to(Point {
+                x: (base.x + to.x) / 2.0,
+                y: (base.y + to.y) / 2.0,
+            })
+            .end_line()
+            .fill_polygon(
+                vec![
+                    to.x,
+                    base.x - half_width * angle.sin(),
+                    base.x + half_width * angle.sin(),
+                ],
+                vec![
+                    to.y,
+                    base.y + half_width * angle.cos(),
+                    base.y - half_width * angle.cos(),
+                ],
+            );
+    }
+
+    fn draw_title<B: Backend>(&self, canvas: &mut B, frame: &Frame) {
+        let (xaxis, yaxis) = (&frame.xaxis, &frame.yaxis);
+        if let Some(ref title) = self.title {
+            canvas.font(font_weight(self.title_bold), self.font_size);
+            canvas.draw_text(
+                Point {
+                    x: (frame.to_canvas_x(xaxis.limits.0) + frame.to_canvas_x(xaxis.limits.1))
+                        / 2.0,
+                    y: frame.to_canvas_y(yaxis.limits.1)
+                        + self.font_size / 2.0
+                        + self.top_labels_height(),
+                },
+                BottomCenter,
+                title,
+            );
+            canvas.font(pdfpdf::Font::Helvetica, self.font_size);
+        }
+    }
+
+    // Draw circles at each radial tick and a spoke every 30 degrees in place of the frame, with
+    // the angles labeled around the outside
+    fn draw_polar_grid<B: Backend>(&self, canvas: &mut B, frame: &Frame) {
+        let to_canvas = |(x, y): (f64, f64)| Point {
+            x: frame.to_canvas_x(x),
+            y: frame.to_canvas_y(y),
         };
+        let radius = frame.yaxis.limits.1;
+
+        canvas.set_color(Color::gray(200)).set_line_width(0.5);
+        for &r in &frame.yaxis.ticks {
+            let points = circle_points((0.0, 0.0), r);
+            canvas.draw_line(
+                points.iter().map(|&p| to_canvas(p).x),
+                points.iter().map(|&p| to_canvas(p).y),
+            );
+        }
+        for degrees in (0..360).step_by(30) {
+            let angle = (degrees as f64).to_radians();
+            canvas
+                .move_to(to_canvas((0.0, 0.0)))
+                .line_to(to_canvas((radius * angle.cos(), radius * angle.sin())))
+                .end_line();
+        }
+
+        let points = circle_points((0.0, 0.0), radius);
+        canvas
+            .set_color(Color::gray(0))
+            .set_line_width(1.0)
+            .draw_line(
+                points.iter().map(|&p| to_canvas(p).x),
+                points.iter().map(|&p| to_canvas(p).y),
+            );
+
+        // Label each spoke just outside the circle, on the side away from the center
+        let gap = self.tick_length;
+        let center = to_canvas((0.0, 0.0));
+        let outer = to_canvas((radius, 0.0)).x - center.x + gap;
+        for degrees in (0..360).step_by(30) {
+            let angle = (degrees as f64).to_radians();
+            let (cos, sin) = (angle.cos(), angle.sin());
+            let alignment = match (cos > 1e-9, cos < -1e-9, sin > 1e-9, sin < -1e-9) {
+                (true, _, true, _) => BottomLeft,
+                (true, _, _, true) => TopLeft,
+                (true, _, _, _) => CenterLeft,
+                (_, true, true, _) => BottomRight,
+                (_, true, _, true) => TopRight,
+                (_, true, _, _) => CenterRight,
+                (_, _, true, _) => BottomCenter,
+                _ => TopCenter,
+            };
+            canvas.draw_text(
+                Point {
+                    x: center.x + outer * cos,
+                    y: center.y + outer * sin,
+                },
+                alignment,
+                &degrees.to_string(),
+            );
+        }
+
+        // Label the radii along the 0 degree spoke
+        for (&r, label) in frame.yaxis.ticks.iter().zip(&frame.yaxis.tick_labels) {
+            let position = to_canvas((r, 0.0));
+            canvas.draw_text(
+                Point {
+                    x: position.x - 2.0,
+                    y: position.y + 2.0,
+                },
+                BottomRight,
+                label,
+            );
+        }
+
+        self.draw_title(canvas, frame);
+    }
+
+    // Draw the plot's border at the margins
+    fn draw_border<B: Backend>(&self, canvas: &mut B, frame: &Frame) {
+        let (xaxis, yaxis) = (&frame.xaxis, &frame.yaxis);
+        canvas.draw_rectangle(
+            Point {
+                x: frame.to_canvas_x(xaxis.limits.0),
+                y: frame.to_canvas_y(yaxis.limits.0),
+            },
+            Size {
+                width: frame.to_canvas_x(xaxis.limits.1) - frame.to_canvas_x(xaxis.limits.0),
+                height: frame.to_canvas_y(yaxis.limits.1) - frame.to_canvas_y(yaxis.limits.0),
+            },
+        );
+    }
 
-        let to_canvas_y = |y| {
-            let y_scale = plot_height / (yaxis.limits.1 - yaxis.limits.0);
-            ((y - yaxis.limits.0) * y_scale) + xaxis.margin
+    fn draw_axes<B: Backend>(&self, canvas: &mut B, frame: &Frame) {
+        let xaxis = &frame.xaxis;
+        let yaxis = &frame.yaxis;
+        let to_canvas_x = |x| frame.to_canvas_x(x);
+        let to_canvas_y = |y| frame.to_canvas_y(y);
+
+        // The data coordinates that the x and y axis lines run along
+        let contains = |(a, b): (f64, f64), v: f64| a.min(b) <= v && v <= a.max(b);
+        let x_spine = if self.spines_zero && contains(yaxis.limits, 0.0) {
+            0.0
+        } else {
+            yaxis.limits.0
+        };
+        let y_spine = if self.spines_zero && contains(xaxis.limits, 0.0) {
+            0.0
+        } else {
+            xaxis.limits.0
         };
 
-        self.draw_axes(&xaxis, &yaxis, to_canvas_x, to_canvas_y);
+        canvas.set_color(Color::gray(0)).set_line_width(1.0);
+        if self.arrow_spines {
+            // Each axis line runs towards its larger limit, whichever side of the plot that's on
+            let (x_from, x_to) = if xaxis.limits.0 <= xaxis.limits.1 {
+                xaxis.limits
+            } else {
+                (xaxis.limits.1, xaxis.limits.0)
+            };
+            let (y_from, y_to) = if yaxis.limits.0 <= yaxis.limits.1 {
+                yaxis.limits
+            } else {
+                (yaxis.limits.1, yaxis.limits.0)
+            };
+            self.draw_arrow(
+                canvas,
+                Point {
+                    x: to_canvas_x(x_from),
+                    y: to_canvas_y(x_spine),
+                },
+                Point {
+                    x: to_canvas_x(x_to),
+                    y: to_canvas_y(x_spine),
+                },
+            );
+            self.draw_arrow(
+                canvas,
+                Point {
+                    x: to_canvas_x(y_spine),
+                    y: to_canvas_y(y_from),
+                },
+                Point {
+                    x: to_canvas_x(y_spine),
+                    y: to_canvas_y(y_to),
+                },
+            );
+        } else if self.spines_zero {
+            // Draw just the two axis lines, across the whole plot area
+            canvas
+                .move_to(Point {
+                    x: to_canvas_x(xaxis.limits.0),
+                    y: to_canvas_y(x_spine),
+                })
+                .line_to(Point {
+                    x: to_canvas_x(xaxis.limits.1),
+                    y: to_canvas_y(x_spine),
+                })
+                .end_line()
+                .move_to(Point {
+                    x: to_canvas_x(y_spine),
+                    y: to_canvas_y(yaxis.limits.0),
+                })
+                .line_to(Point {
+                    x: to_canvas_x(y_spine),
+                    y: to_canvas_y(yaxis.limits.1),
+                })
+                .end_line();
+        } else {
+            self.draw_border(canvas, frame);
+        }
+
+        // Draw the x tick marks
+        for (&x, label) in xaxis.ticks.iter().zip(&xaxis.tick_labels) {
+            canvas
+                .move_to(Point {
+                    x: to_canvas_x(x),
+                    y: to_canvas_y(x_spine),
+                })
+                .line_to(Point {
+                    x: to_canvas_x(x),
+                    y: to_canvas_y(x_spine) - self.tick_length,
+                })
+                .end_line();
+            canvas.draw_text(
+                Point {
+                    x: to_canvas_x(x),
+                    y: to_canvas_y(x_spine) - self.tick_length,
+                },
+                TopCenter,
+                label,
+            );
+        }
 
-        // Draw the data series
-        if !x_values.is_empty() {
-            self.pdf
-                .set_clipping_box(
+        // Draw the secondary x axis along the top, mirroring the bottom ticks
+        if let Some(SecondaryAxis {
+            ref transform,
+            ref label,
+        }) = self.secondary_xaxis
+        {
+            let top = to_canvas_y(yaxis.limits.1);
+            for &x in &xaxis.ticks {
+                canvas
+                    .move_to(Point {
+                        x: to_canvas_x(x),
+                        y: top,
+                    })
+                    .line_to(Point {
+                        x: to_canvas_x(x),
+                        y: top + self.tick_length,
+                    })
+                    .end_line();
+                canvas.draw_text(
                     Point {
-                        x: to_canvas_x(xaxis.limits.0) - 2.0,
-                        y: to_canvas_y(yaxis.limits.0) - 2.0,
-                    },
-                    Size {
-                        width: to_canvas_x(xaxis.limits.1) - to_canvas_x(xaxis.limits.0) + 4.0,
-                        height: to_canvas_y(yaxis.limits.1) - to_canvas_y(yaxis.limits.0) + 4.0,
+                        x: to_canvas_x(x),
+                        y: top + self.tick_length,
                     },
-                )
-                .set_line_width(1.5)
-                .set_color(Color {
-                    red: 31,
-                    green: 119,
-                    blue: 180,
+                    BottomCenter,
+                    &short_number(transform(x)),
+                );
+            }
+            canvas.font(font_weight(self.label_bold), self.font_size);
+            canvas.draw_text(
+                Point {
+                    x: (to_canvas_x(xaxis.limits.0) + to_canvas_x(xaxis.limits.1)) / 2.0,
+                    y: top + self.tick_length + self.font_size * 1.5,
+                },
+                BottomCenter,
+                label,
+            );
+            canvas.font(pdfpdf::Font::Helvetica, self.font_size);
+        }
+
+        // Whether an x tick label reaches left under where the y tick labels end, so that a y label
+        // level with the x axis would run into it at the corner
+        let y_labels_right = to_canvas_x(y_spine) - self.tick_length - 2.0;
+        let corner_clash = xaxis
+            .ticks
+            .iter()
+            .zip(&xaxis.tick_labels)
+            .any(|(&x, label)| to_canvas_x(x) - self.width_of(label) / 2.0 < y_labels_right);
+
+        // Draw the y tick marks
+        for (&y, label) in yaxis.ticks.iter().zip(&yaxis.tick_labels) {
+            canvas
+                .move_to(Point {
+                    x: to_canvas_x(y_spine),
+                    y: to_canvas_y(y),
                 })
-                .draw_line(
-                    x_values.iter().map(|&v| to_canvas_x(v)),
-                    y_values.iter().map(|&v| to_canvas_y(v)),
-                )
-                .set_color(Color::gray(0));
+                .line_to(Point {
+                    x: to_canvas_x(y_spine) - self.tick_length,
+                    y: to_canvas_y(y),
+                })
+                .end_line();
+            // Sit a clashing label on top of its tick instead of centering it
+            let near_x_axis = (to_canvas_y(y) - to_canvas_y(x_spine)).abs() < self.font_size / 2.0;
+            let alignment = if corner_clash && near_x_axis {
+                BottomRight
+            } else {
+                CenterRight
+            };
+            canvas.draw_text(
+                Point {
+                    x: y_labels_right,
+                    y: to_canvas_y(y),
+                },
+                alignment,
+                label,
+            );
         }
 
-        self
+        self.draw_title(canvas, frame);
+
+        canvas.font(font_weight(self.label_bold), self.font_size);
+
+        // Draw the x label
+        if let Some(ref xlabel) = self.xlabel {
+            canvas.draw_text(
+                Point {
+                    x: (to_canvas_x(xaxis.limits.0) + to_canvas_x(xaxis.limits.1)) / 2.0,
+                    y: 4.0 + self.font_size / 2.0 + self.caption_height(frame.width),
+                },
+                BottomCenter,
+                xlabel,
+            );
+        }
+
+        // Draw the y label
+        if let Some(ref ylabel) = self.ylabel {
+            let middle = (to_canvas_y(yaxis.limits.0) + to_canvas_y(yaxis.limits.1)) / 2.0;
+            match self.ylabel_rotation {
+                0 => {
+                    canvas.draw_text(Point { x: 6.0, y: middle }, CenterLeft, ylabel);
+                }
+                // Upright labels hang from the left edge
+                90 => {
+                    canvas.draw_rotated_text(Point { x: 6.0, y: middle }, TopCenter, 90, ylabel);
+                }
+                degrees => {
+                    canvas.draw_rotated_text(
+                        Point {
+                            x: 6.0 + self.ylabel_extent() / 2.0,
+                            y: middle,
+                        },
+                        CenterCenter,
+                        degrees,
+                        ylabel,
+                    );
+                }
+            }
+        }
+
+        // Draw the caption, with its last line at the bottom of the page
+        let caption_lines = self.caption_lines(frame.width);
+        let caption_font_size = self.caption_font_size();
+        canvas.font(pdfpdf::Font::Helvetica, caption_font_size);
+        for (i, line) in caption_lines.iter().rev().enumerate() {
+            canvas.draw_text(
+                Point {
+                    x: self.font_size / 2.0,
+                    y: 4.0 + i as f64 * caption_font_size * 1.2,
+                },
+                BottomLeft,
+                line,
+            );
+        }
+
+        // Tick labels are measured in the regular weight, so always switch back
+        canvas.font(pdfpdf::Font::Helvetica, self.font_size);
     }
 
-    pub fn image(
+    /// Plot a series as a line, and markers if they're set
+    ///
+    /// Points where x or y is NaN or infinite are left out of the autoscaled limits, break the
+    /// line, and get no marker.
+    pub fn plot(&mut self, x_values: &[f64], y_values: &[f64]) -> &mut Self {
+        self.add_series(x_values, y_values, SeriesStyle::default())
+    }
+
+    /// Plot a series with its own color, line style, line width, or marker
+    pub fn plot_as(&mut self, x_values: &[f64], y_values: &[f64], style: SeriesStyle) -> &mut Self {
+        self.add_series(x_values, y_values, style)
+    }
+
+    /// Plot complex values on the complex plane, real part along x and imaginary part along y
+    pub fn plot_complex(&mut self, values: &[(f64, f64)]) -> &mut Self {
+        let (x_values, y_values): (Vec<f64>, Vec<f64>) = values.iter().cloned().unzip();
+        self.add_series(&x_values, &y_values, SeriesStyle::default())
+    }
+
+    /// Plot one part of complex values, given as parallel real and imaginary parts, against x
+    pub fn plot_complex_as(
         &mut self,
-        image_data: &[f64],
-        image_width: usize,
-        image_height: usize,
+        x_values: &[f64],
+        re: &[f64],
+        im: &[f64],
+        mode: ComplexMode,
     ) -> &mut Self {
-        // Convert the image to u8 and apply a color map
-        assert!(image_width * image_height == image_data.len());
+        assert!(
+            x_values.len() == re.len() && re.len() == im.len(),
+            "x, re, and im must have the same length, got {}, {}, and {}",
+            x_values.len(),
+            re.len(),
+            im.len()
+        );
+        let y_values = re
+            .iter()
+            .zip(im)
+            .map(|(&re, &im)| match mode {
+                ComplexMode::Magnitude => re.hypot(im),
+                ComplexMode::Phase => im.atan2(re),
+                ComplexMode::Real => re,
+                ComplexMode::Imaginary => im,
+            })
+            .collect::<Vec<_>>();
+        self.add_series(x_values, &y_values, SeriesStyle::default())
+    }
 
-        let mut png_bytes = Vec::with_capacity(image_data.len() * 3);
-        let mut max = std::f64::MIN;
-        let mut min = std::f64::MAX;
-        for i in image_data
+    /// Draw the unit circle in gray, for reference on the complex plane
+    pub fn unit_circle(&mut self) -> &mut Self {
+        let points = circle_points((0.0, 0.0), 1.0);
+        self.add_cartesian(Item::Segments {
+            segments: points.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+            color: Color::gray(127),
+        })
+    }
+
+    /// Draw gray circles of each of `radii` around `center`, in data units, as a target to plot
+    /// hits over
+    pub fn bullseye(&mut self, center: (f64, f64), radii: &[f64]) -> &mut Self {
+        assert!(
+            radii.iter().all(|&r| r > 0.0 && r.is_finite()),
+            "bullseye radii must be positive, got {:?}",
+            radii
+        );
+        let segments = radii
             .iter()
-            .filter(|i| !i.is_nan() && !i.is_infinite())
-        {
-            if *i < min {
-                min = *i;
-            }
-            if *i > max {
-                max = *i;
-            }
-        }
+            .flat_map(|&radius| {
+                let points = circle_points(center, radius);
+                points
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        self.add_cartesian(Item::Segments {
+            segments,
+            color: Color::gray(127),
+        })
+    }
 
-        let map = colormaps::VIRIDIS;
-        for i in image_data {
-            if i.is_nan() || i.is_infinite() {
-                png_bytes.extend(&[255, 255, 255]);
-            } else {
-                let i = i.max(min); // upper-end clipping is applied by the line below
-                let index = ((i - min) / (max - min) * 255.0) as usize;
-                png_bytes.push((map[index][0] * 255.0) as u8);
-                png_bytes.push((map[index][1] * 255.0) as u8);
-                png_bytes.push((map[index][2] * 255.0) as u8);
-            }
+    /// Draw a `bullseye` over rings shaded in alternating light tones, starting from the outside
+    pub fn bullseye_shaded(&mut self, center: (f64, f64), radii: &[f64]) -> &mut Self {
+        assert!(
+            radii.iter().all(|&r| r > 0.0 && r.is_finite()),
+            "bullseye radii must be positive, got {:?}",
+            radii
+        );
+        let mut radii = radii.to_vec();
+        radii.sort_by(|a, b| b.total_cmp(a));
+        // Each disc covers the middle of the one before it, which leaves a ring of each tone
+        let discs = radii
+            .iter()
+            .enumerate()
+            .map(|(i, &radius)| {
+                let (x, y) = circle_points(center, radius).into_iter().unzip();
+                let tone = if i % 2 == 0 { 235 } else { 250 };
+                (x, y, Color::gray(tone))
+            })
+            .collect();
+        let zorder = self.zorder;
+        // A pattern on the outer discs would show through the inner ones, so these stay solid
+        self.add_cartesian(Item::Polygons {
+            polygons: discs,
+            hatch: None,
+        });
+        self.zorder = zorder;
+        self.bullseye(center, &radii)
+    }
+
+    /// Plot several y series against the same x values, each in the next color of the cycle
+    pub fn plot_multi(&mut self, x_values: &[f64], ys: &[&[f64]]) -> &mut Self {
+        for y_values in ys {
+            assert!(
+                x_values.len() == y_values.len(),
+                "x has {} values but a y series has {}",
+                x_values.len(),
+                y_values.len()
+            );
+        }
+        let zorder = self.zorder.take();
+        for y_values in ys {
+            self.zorder = zorder;
+            self.add_series(x_values, y_values, SeriesStyle::default());
         }
+        self
+    }
 
-        let (xaxis, yaxis) = self.digest_tick_settings(&[], &[]);
+    /// Set the drawing order of the next thing added to the plot; higher z is drawn on top of
+    /// lower z, and equal z is drawn in the order things were added
+    pub fn zorder(&mut self, z: i32) -> &mut Self {
+        self.zorder = Some(z);
+        self
+    }
 
-        let width = self.width;
-        let height = self.height;
+    fn add_series(
+        &mut self,
+        x_values: &[f64],
+        y_values: &[f64],
+        mut style: SeriesStyle,
+    ) -> &mut Self {
+        if let Some(ref linestyle) = style.linestyle {
+            linestyle.validate();
+        }
+        let color = self.next_color();
+        style.color.get_or_insert(color);
+        let (x_values, mut columns) = self.fold(x_values, &[y_values]);
+        self.add(Item::Series(Series {
+            x_values,
+            y_values: columns.remove(0),
+            style,
+        }))
+    }
 
-        let plot_width =
-            width - yaxis.margin - self.pdf.width_of(xaxis.tick_labels.last().unwrap());
-        let plot_height = height - xaxis.margin - self.font_size;
-        let plot_size = plot_width.min(plot_height);
-
-        // This is a hack; we adjust the height and width so that the generated PDF file has its
-        // dimensions adjusted
-        // TODO: This change should be ephemeral
-        self.height = plot_size + xaxis.margin + self.font_size;
-        self.width = plot_size + yaxis.margin + self.font_size;
-
-        // Function to convert from plot pixels to canvas pixels
-        let to_canvas_x = |x| {
-            let x_scale = plot_size / (xaxis.limits.1 - xaxis.limits.0);
-            ((x - xaxis.limits.0) * x_scale) + yaxis.margin
-        };
-
-        let to_canvas_y = |y| {
-            let y_scale = plot_size / (yaxis.limits.1 - yaxis.limits.0);
-            ((y - yaxis.limits.0) * y_scale) + xaxis.margin
-        };
-
-        self.draw_axes(&xaxis, &yaxis, to_canvas_x, to_canvas_y);
-
-        let x_extent = to_canvas_x(xaxis.limits.1) - to_canvas_x(xaxis.limits.0) - 1.0;
-        let y_extent = to_canvas_y(yaxis.limits.1) - to_canvas_y(yaxis.limits.0) - 1.0;
-        self.pdf.transform(
-            Matrix::scale(
-                x_extent / (image_width as f64),
-                y_extent / (image_width as f64),
-            ) * Matrix::translate(
-                to_canvas_x(xaxis.limits.0) + 0.5,
-                to_canvas_y(yaxis.limits.0) + 0.5,
+    // Apply `phase_fold`, if it's set, to x and the columns that go with it
+    fn fold(&self, x_values: &[f64], columns: &[&[f64]]) -> (Vec<f64>, Vec<Vec<f64>>) {
+        match self.phase_fold {
+            Some((period, repeat)) => phase_fold(x_values, columns, period, repeat),
+            None => (
+                x_values.to_vec(),
+                columns.iter().map(|column| column.to_vec()).collect(),
             ),
+        }
+    }
+
+    // The color cycle advances with each series, in the order of the calls rather than the
+    // drawing order
+    fn next_color(&self) -> Color {
+        let num_series = self
+            .items
+            .iter()
+            .filter(|(_, item)| matches!(item, Item::Series(_) | Item::ErrorBars { .. }))
+            .count();
+        self.colors[num_series % self.colors.len()]
+    }
+
+    /// Draw a marker at each point with a bar from `y - yerr_lo` up to `y + yerr_hi`
+    ///
+    /// Points where x, y, or either end of the bar is NaN or infinite are left out.
+    pub fn errorbar_asym(
+        &mut self,
+        x_values: &[f64],
+        y_values: &[f64],
+        yerr_lo: &[f64],
+        yerr_hi: &[f64],
+    ) -> &mut Self {
+        assert!(
+            x_values.len() == y_values.len()
+                && y_values.len() == yerr_lo.len()
+                && y_values.len() == yerr_hi.len(),
+            "x, y, and both errors must have the same length, got {}, {}, {}, and {}",
+            x_values.len(),
+            y_values.len(),
+            yerr_lo.len(),
+            yerr_hi.len()
         );
-        self.pdf.add_image_at(
-            pdfpdf::Image::new(&png_bytes, image_width as u64, image_height as u64),
-            pdfpdf::Point { x: 0, y: 0 },
-        );
+        let (mut xs, mut ys, mut lower, mut upper) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for i in 0..x_values.len() {
+            let (x, y) = (x_values[i], y_values[i]);
+            let (lo, hi) = (y - yerr_lo[i], y + yerr_hi[i]);
+            if [x, y, lo, hi].iter().all(|v| v.is_finite()) {
+                xs.push(x);
+                ys.push(y);
+                lower.push(lo);
+                upper.push(hi);
+            }
+        }
+        let color = self.next_color();
+        self.add(Item::ErrorBars {
+            x_values: xs,
+            y_values: ys,
+            lower,
+            upper,
+            color,
+        })
+    }
+
+    // Record something to draw once the axes are known
+    fn add(&mut self, item: Item) -> &mut Self {
+        let item = if self.polar {
+            item.into_cartesian()
+        } else {
+            item
+        };
+        self.add_cartesian(item)
+    }
+
+    // Add geometry that's already in x and y, such as circles, without converting it from polar
+    fn add_cartesian(&mut self, item: Item) -> &mut Self {
+        let zorder = self.zorder.take().unwrap_or_else(|| item.default_zorder());
+        self.items.push((zorder, item));
         self
     }
 
-    pub fn write_to<F>(&mut self, filename: F) -> std::io::Result<()>
+    /// Draw a node-link diagram, with a line for each edge between two indices into `node_xy`
+    pub fn graph(&mut self, node_xy: &[(f64, f64)], edges: &[(usize, usize)]) -> &mut Self {
+        for &(start, end) in edges {
+            assert!(
+                start < node_xy.len() && end < node_xy.len(),
+                "edge ({}, {}) refers to a node that does not exist, there are only {} nodes",
+                start,
+                end,
+                node_xy.len()
+            );
+        }
+
+        let (x_values, y_values): (Vec<f64>, Vec<f64>) = node_xy.iter().cloned().unzip();
+        let zorder = self.zorder;
+        self.add(Item::Segments {
+            segments: edges
+                .iter()
+                .map(|&(start, end)| (node_xy[start], node_xy[end]))
+                .collect(),
+            color: Color::gray(127),
+        });
+        self.zorder = zorder;
+        self.add(Item::Markers {
+            x_values,
+            y_values,
+            color: self.colors[0],
+        })
+    }
+
+    /// Scatter the points and draw their least-squares line across the x limits
+    ///
+    /// Points where either coordinate is not finite are left out of the fit. The slope and
+    /// intercept are available afterwards from `regression_fit`. If every x value is the same
+    /// there is no line to fit, so only the points are drawn.
+    pub fn regression(&mut self, x_values: &[f64], y_values: &[f64]) -> &mut Self {
+        assert!(
+            x_values.len() == y_values.len(),
+            "x has {} values but y has {}",
+            x_values.len(),
+            y_values.len()
+        );
+        let (x_values, y_values): (Vec<f64>, Vec<f64>) = x_values
+            .iter()
+            .cloned()
+            .zip(y_values.iter().cloned())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .unzip();
+        assert!(
+            x_values.len() >= 2,
+            "a linear fit needs at least 2 finite points"
+        );
+
+        let n = x_values.len() as f64;
+        let x_mean = x_values.iter().sum::<f64>() / n;
+        let y_mean = y_values.iter().sum::<f64>() / n;
+        let (covariance, x_variance) = x_values.iter().zip(&y_values).fold(
+            (0.0, 0.0),
+            |(covariance, x_variance), (&x, &y)| {
+                (
+                    covariance + (x - x_mean) * (y - y_mean),
+                    x_variance + (x - x_mean).powi(2),
+                )
+            },
+        );
+
+        let zorder = self.zorder;
+        self.add(Item::Markers {
+            x_values,
+            y_values,
+            color: self.colors[0],
+        });
+        if x_variance == 0.0 {
+            self.fit = None;
+            return self;
+        }
+        let slope = covariance / x_variance;
+        let intercept = y_mean - slope * x_mean;
+        self.fit = Some((slope, intercept));
+        self.zorder = zorder;
+        self.add(Item::Line {
+            slope,
+            intercept,
+            color: self.colors[1 % self.colors.len()],
+        })
+    }
+
+    /// The `(slope, intercept)` computed by the most recent call to `regression`, or `None` if
+    /// its x values were all the same
+    pub fn regression_fit(&self) -> Option<(f64, f64)> {
+        self.fit
+    }
+
+    /// Draw an image, with row 0 at the bottom unless `image_origin` says otherwise
+    ///
+    /// The image spans the axis limits where they are set, and otherwise one unit per pixel.
+    /// Pixels are squares that cover their cell of the extent, so pixel `(i, j)` is centered at
+    /// `x0 + (i + 0.5) * (x1 - x0) / width` and likewise in y; with the default extent that is
+    /// `(i + 0.5, j + 0.5)`.
+    pub fn image(
+        &mut self,
+        image_data: &[f64],
+        image_width: usize,
+        image_height: usize,
+    ) -> &mut Self {
+        self.draw_image(
+            image_data,
+            image_width,
+            image_height,
+            None,
+            self.image_origin,
+        )
+    }
+
+    /// Bin points into a `x_bins` by `y_bins` grid spanning the data and draw the count in each
+    /// cell as an image. Empty cells are drawn in the same color as missing image data.
+    pub fn hist2d(
+        &mut self,
+        x_values: &[f64],
+        y_values: &[f64],
+        x_bins: usize,
+        y_bins: usize,
+    ) -> &mut Self {
+        assert!(
+            x_values.len() == y_values.len(),
+            "x has {} values but y has {}",
+            x_values.len(),
+            y_values.len()
+        );
+        assert!(
+            x_bins > 0 && y_bins > 0,
+            "need at least one bin along each axis"
+        );
+
+        let points = x_values
+            .iter()
+            .cloned()
+            .zip(y_values.iter().cloned())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect::<Vec<_>>();
+        assert!(!points.is_empty(), "hist2d needs at least one finite point");
+
+        let x_range = points
+            .iter()
+            .map(|p| p.0)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |r, x| {
+                (r.0.min(x), r.1.max(x))
+            });
+        let y_range = points
+            .iter()
+            .map(|p| p.1)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |r, y| {
+                (r.0.min(y), r.1.max(y))
+            });
+
+        // The maximum lands on the far edge of the last bin, so clamp it into that bin
+        let bin = |v: f64, (min, max): (f64, f64), bins: usize| {
+            if max > min {
+                (((v - min) / (max - min) * bins as f64) as usize).min(bins - 1)
+            } else {
+                0
+            }
+        };
+        let mut counts = vec![0.0; x_bins * y_bins];
+        for &(x, y) in &points {
+            counts[bin(y, y_range, y_bins) * x_bins + bin(x, x_range, x_bins)] += 1.0;
+        }
+        for count in &mut counts {
+            if *count == 0.0 {
+                *count = f64::NAN;
+            }
+        }
+
+        self.draw_image(
+            &counts,
+            x_bins,
+            y_bins,
+            Some((x_range, y_range)),
+            Origin::Lower,
+        )
+    }
+
+    /// Draw a time-frequency grid of magnitudes as an image, with time along x and frequency
+    /// along y in the units of `time_step` and `freq_step`
+    ///
+    /// `magnitudes[j * n_times + i]` is the magnitude at time `i * time_step` and frequency
+    /// `j * freq_step`, and covers the cell from there to the next time and frequency.
+    pub fn spectrogram(
+        &mut self,
+        magnitudes: &[f64],
+        n_times: usize,
+        n_freqs: usize,
+        time_step: f64,
+        freq_step: f64,
+    ) -> &mut Self {
+        assert!(
+            time_step > 0.0 && freq_step > 0.0,
+            "time and frequency steps must be positive, got {} and {}",
+            time_step,
+            freq_step
+        );
+        self.draw_image(
+            magnitudes,
+            n_times,
+            n_freqs,
+            Some((
+                (0.0, n_times as f64 * time_step),
+                (0.0, n_freqs as f64 * freq_step),
+            )),
+            Origin::Lower,
+        )
+    }
+
+    /// Bin points into hexagonal cells, `gridsize` across the x range of the data, and fill each
+    /// cell that has any points with its count colored by the colormap
+    pub fn hexbin(&mut self, x_values: &[f64], y_values: &[f64], gridsize: usize) -> &mut Self {
+        assert!(
+            x_values.len() == y_values.len(),
+            "x has {} values but y has {}",
+            x_values.len(),
+            y_values.len()
+        );
+        assert!(gridsize > 0, "need at least one hexagon across");
+
+        let points = x_values
+            .iter()
+            .cloned()
+            .zip(y_values.iter().cloned())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect::<Vec<_>>();
+        assert!(!points.is_empty(), "hexbin needs at least one finite point");
+
+        let (xmin, xmax) = points
+            .iter()
+            .map(|p| p.0)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |r, x| {
+                (r.0.min(x), r.1.max(x))
+            });
+        let (ymin, ymax) = points
+            .iter()
+            .map(|p| p.1)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |r, y| {
+                (r.0.min(y), r.1.max(y))
+            });
+
+        // Hexagons are centered on two interleaved rectangular lattices; the second is offset by
+        // half a cell in each direction. Cell sizes are chosen so that the hexagons are regular
+        // when x and y have the same range.
+        let nx = gridsize;
+        let ny = ((gridsize as f64 / 3f64.sqrt()).round() as usize).max(1);
+        let sx = if xmax > xmin {
+            (xmax - xmin) / nx as f64
+        } else {
+            1.0
+        };
+        let sy = if ymax > ymin {
+            (ymax - ymin) / ny as f64
+        } else {
+            1.0
+        };
+
+        let mut counts = std::collections::HashMap::new();
+        for &(x, y) in &points {
+            let (x, y) = ((x - xmin) / sx, (y - ymin) / sy);
+            let (i1, j1) = (x.round(), y.round());
+            let (i2, j2) = (x.floor() + 0.5, y.floor() + 0.5);
+            // y distances count three times as much because the lattice rows are closer together
+            let d1 = (x - i1).powi(2) + 3.0 * (y - j1).powi(2);
+            let d2 = (x - i2).powi(2) + 3.0 * (y - j2).powi(2);
+            let center = if d1 <= d2 { (i1, j1) } else { (i2, j2) };
+            // Lattice centers are multiples of one half, so doubling them gives exact keys
+            *counts
+                .entry(((center.0 * 2.0) as i64, (center.1 * 2.0) as i64))
+                .or_insert(0usize) += 1;
+        }
+
+        let max = *counts.values().max().unwrap() as f64;
+        let min = *counts.values().min().unwrap() as f64;
+        // HashMap order isn't stable, and the output should be
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_unstable();
+        let cells = counts
+            .into_iter()
+            .map(|((i, j), count)| {
+                let (cx, cy) = (xmin + i as f64 / 2.0 * sx, ymin + j as f64 / 2.0 * sy);
+                (
+                    [0.5, 0.5, 0.0, -0.5, -0.5, 0.0]
+                        .iter()
+                        .map(|dx| cx + dx * sx)
+                        .collect(),
+                    [-0.5, 0.5, 1.0, 0.5, -0.5, -1.0]
+                        .iter()
+                        .map(|dy| cy + dy * sy / 3.0)
+                        .collect(),
+                    self.colorize(count as f64, (min, max)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The colors carry the counts, which a pattern would hide
+        self.add(Item::Polygons {
+            polygons: cells,
+            hatch: None,
+        })
+    }
+
+    /// Draw a line whose segments are colored by the colormap, using the average of `c` at the two
+    /// ends of each segment
+    pub fn plot_gradient(&mut self, x_values: &[f64], y_values: &[f64], c: &[f64]) -> &mut Self {
+        assert!(
+            x_values.len() == y_values.len() && y_values.len() == c.len(),
+            "x, y, and c must have the same length, got {}, {}, and {}",
+            x_values.len(),
+            y_values.len(),
+            c.len()
+        );
+        let range = c
+            .iter()
+            .filter(|c| c.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |r, &c| {
+                (r.0.min(c), r.1.max(c))
+            });
+        let colors = c
+            .windows(2)
+            .map(|pair| self.colorize((pair[0] + pair[1]) / 2.0, range))
+            .collect();
+        self.add(Item::GradientLine {
+            x_values: x_values.to_vec(),
+            y_values: y_values.to_vec(),
+            colors,
+        })
+    }
+
+    // Pick the colormap's color for a value within `(min, max)`
+    fn colorize(&self, value: f64, (min, max): (f64, f64)) -> Color {
+        let position = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0) * 255.0
+        } else {
+            0.0
+        };
+        let color = sample_colormap(self.colormap.table(), position, self.interpolate_colormap);
+        Color {
+            red: (color[0] * 255.0) as u8,
+            green: (color[1] * 255.0) as u8,
+            blue: (color[2] * 255.0) as u8,
+        }
+    }
+
+    // Color scalar data with the colormap and add it as an image
+    fn draw_image(
+        &mut self,
+        image_data: &[f64],
+        image_width: usize,
+        image_height: usize,
+        extent: Option<((f64, f64), (f64, f64))>,
+        origin: Origin,
+    ) -> &mut Self {
+        // Convert the image to u8 and apply a color map
+        assert!(image_width * image_height == image_data.len());
+
+        let mut png_bytes = Vec::with_capacity(image_data.len() * 3);
+        let mut max = std::f64::MIN;
+        let mut min = std::f64::MAX;
+        for i in image_data
+            .iter()
+            .filter(|i| !i.is_nan() && !i.is_infinite())
+        {
+            if *i < min {
+                min = *i;
+            }
+            if *i > max {
+                max = *i;
+            }
+        }
+
+        let map = self.colormap.table();
+        for i in rows_from_bottom(image_data, image_width, origin) {
+            if i.is_nan() || i.is_infinite() {
+                png_bytes.extend(&[255, 255, 255]);
+            } else {
+                let i = i.max(min); // upper-end clipping is applied by the line below
+                let position = colormap_position(i, (min, max));
+                let color = sample_colormap(map, position, self.interpolate_colormap);
+                png_bytes.push((color[0] * 255.0) as u8);
+                png_bytes.push((color[1] * 255.0) as u8);
+                png_bytes.push((color[2] * 255.0) as u8);
+            }
+        }
+
+        self.add(Item::Image(Image {
+            png_bytes,
+            width: image_width,
+            height: image_height,
+            extent,
+        }))
+    }
+
+    /// Draw an image of colormap indices that have already been normalized to 0 through 255
+    pub fn image_indexed(
+        &mut self,
+        indices: &[u8],
+        image_width: usize,
+        image_height: usize,
+        map: Colormap,
+    ) -> &mut Self {
+        assert!(image_width * image_height == indices.len());
+
+        let table = map.table();
+        let mut png_bytes = Vec::with_capacity(indices.len() * 3);
+        for &index in rows_from_bottom(indices, image_width, self.image_origin) {
+            let color = table[index as usize];
+            png_bytes.push((color[0] * 255.0) as u8);
+            png_bytes.push((color[1] * 255.0) as u8);
+            png_bytes.push((color[2] * 255.0) as u8);
+        }
+
+        self.add(Item::Image(Image {
+            png_bytes,
+            width: image_width,
+            height: image_height,
+            extent: None,
+        }))
+    }
+
+    /// Shade a light vertical band behind the data for each `(start, end)` interval in data
+    /// coordinates
+    pub fn axvspans(&mut self, intervals: &[(f64, f64)]) -> &mut Self {
+        self.add(Item::VSpans {
+            intervals: intervals.to_vec(),
+            hatch: self.hatch,
+        })
+    }
+
+    /// Shade a band from `start` to `end` along x behind the data, with `label` centered at the
+    /// top of it, such as to call out a period in a time series
+    pub fn highlight_xrange(
+        &mut self,
+        start: f64,
+        end: f64,
+        label: &str,
+        color: Color,
+    ) -> &mut Self {
+        self.add(Item::Highlight {
+            start: start.min(end),
+            end: start.max(end),
+            label: label.to_string(),
+            color,
+            hatch: self.hatch,
+        })
+    }
+
+    /// Draw a dashed vertical line at each of the `quantiles` (from 0 to 1) of `samples`,
+    /// labeled with its value near the top of the plot
+    pub fn quantile_lines(&mut self, samples: &[f64], quantiles: &[f64]) -> &mut Self {
+        for &q in quantiles {
+            assert!(
+                (0.0..=1.0).contains(&q),
+                "quantiles must be between 0 and 1, got {}",
+                q
+            );
+        }
+        let zorder = self.zorder.take();
+        for &q in quantiles {
+            let x = percentile(samples, q * 100.0)
+                .expect("quantile_lines needs at least one finite sample");
+            self.zorder = zorder;
+            self.add(Item::VLine {
+                x,
+                label: format!("{:.3}", x),
+            });
+        }
+        self
+    }
+
+    /// Draw a Gaussian kernel density estimate of the samples as a line
+    ///
+    /// The bandwidth defaults to Silverman's rule of thumb. Samples that aren't finite are left
+    /// out, and the curve is evaluated out to three bandwidths past the extreme samples.
+    pub fn kde(&mut self, samples: &[f64], bandwidth: Option<f64>) -> &mut Self {
+        let samples = samples
+            .iter()
+            .cloned()
+            .filter(|v| v.is_finite())
+            .collect::<Vec<_>>();
+        assert!(!samples.is_empty(), "kde needs at least one finite sample");
+
+        let n = samples.len() as f64;
+        let bandwidth = bandwidth.unwrap_or_else(|| {
+            let mean = samples.iter().sum::<f64>() / n;
+            let std = (samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+            // There's at least one sample, so both percentiles exist
+            let iqr = percentile(&samples, 75.0).unwrap() - percentile(&samples, 25.0).unwrap();
+            let spread = if iqr > 0.0 { std.min(iqr / 1.34) } else { std };
+            0.9 * spread * n.powf(-0.2)
+        });
+        assert!(
+            bandwidth > 0.0 && bandwidth.is_finite(),
+            "kde needs a positive bandwidth, got {}; the samples may all be the same",
+            bandwidth
+        );
+
+        let min = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = samples.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let x_values = linspace(min - 3.0 * bandwidth, max + 3.0 * bandwidth, 512);
+        let norm = 1.0 / (n * bandwidth * (2.0 * std::f64::consts::PI).sqrt());
+        let y_values = x_values
+            .iter()
+            .map(|x| {
+                norm * samples
+                    .iter()
+                    .map(|s| (-0.5 * ((x - s) / bandwidth).powi(2)).exp())
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+
+        self.add_series(&x_values, &y_values, SeriesStyle::default())
+    }
+
+    /// Draw a table of numbers instead of a plot, with a bold row of headers and each column as
+    /// wide as its widest entry. NaN values are left blank.
+    pub fn table(&mut self, headers: &[&str], rows: &[Vec<f64>]) -> &mut Self {
+        for row in rows {
+            assert!(
+                row.len() == headers.len(),
+                "there are {} headers but a row has {} values",
+                headers.len(),
+                row.len()
+            );
+        }
+        self.table = Some(Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: rows.to_vec(),
+        });
+        self
+    }
+
+    /// How many decimal places the numbers in a `table` are written with, 2 by default
+    pub fn table_precision(&mut self, digits: usize) -> &mut Self {
+        self.table_precision = digits;
+        self
+    }
+
+    /// Start a line series to add points to one at a time with `push_point`, for data that arrives
+    /// in pieces. The series takes its place in the drawing order and color cycle now.
+    pub fn begin_series(&mut self) -> SeriesHandle {
+        self.add_series(&[], &[], SeriesStyle::default());
+        SeriesHandle {
+            index: self.items.len() - 1,
+            phase_fold: self.phase_fold,
+            polar: self.polar,
+            figure: self.figure,
+        }
+    }
+
+    /// Add a point to the end of a series started with `begin_series`, or in order of phase if it
+    /// was begun under `phase_fold`
+    pub fn push_point(&mut self, handle: &SeriesHandle, x: f64, y: f64) -> &mut Self {
+        let (x, y) = if handle.polar {
+            (y * x.cos(), y * x.sin())
+        } else {
+            (x, y)
+        };
+        let series = self.handle_series(handle);
+        match handle.phase_fold {
+            // Keep the series in order of phase, which puts a repeated copy after all the points
+            // of the first
+            Some((period, repeat)) => {
+                if !(x.is_finite() && y.is_finite()) {
+                    return self;
+                }
+                let phase = x.rem_euclid(period);
+                let copies = if repeat { 2 } else { 1 };
+                for copy in 0..copies {
+                    let x = phase + copy as f64 * period;
+                    let at = series.x_values.partition_point(|&v| v <= x);
+                    series.x_values.insert(at, x);
+                    series.y_values.insert(at, y);
+                }
+            }
+            None => {
+                series.x_values.push(x);
+                series.y_values.push(y);
+            }
+        }
+        self
+    }
+
+    /// Stop adding to a series; it's drawn with whatever points it has whether or not this is
+    /// called
+    pub fn finish(&mut self, handle: SeriesHandle) -> &mut Self {
+        let series = self.handle_series(&handle);
+        series.x_values.shrink_to_fit();
+        series.y_values.shrink_to_fit();
+        self
+    }
+
+    // Items are kept in the order they were added, so a handle's index stays put until the
+    // figure is cleared
+    fn handle_series(&mut self, handle: &SeriesHandle) -> &mut Series {
+        assert!(
+            handle.figure == self.figure,
+            "this series was begun in a figure that has since been finished with finish_figure"
+        );
+        match self.items.get_mut(handle.index) {
+            Some((_, Item::Series(series))) => series,
+            _ => panic!("this handle does not refer to a series begun with begin_series"),
+        }
+    }
+
+    /// Plot `f` at `samples` evenly spaced points across the x limits: the ones set with `xlim`, or
+    /// else the ones that the data added so far would get
+    ///
+    /// The line is broken wherever `f` isn't finite. In a polar plot without `xlim`, `f` is
+    /// sampled once around the circle.
+    pub fn plot_fn(&mut self, f: impl Fn(f64) -> f64, samples: usize) -> &mut Self {
+        let (start, end) = match self.xlim {
+            Some(limits) => limits,
+            None if self.polar => (0.0, 2.0 * std::f64::consts::PI),
+            None => {
+                assert!(
+                    !self.items.is_empty(),
+                    "plot_fn needs an x range, from xlim or from data plotted before it"
+                );
+                self.resolved_axes().0.limits
+            }
+        };
+        self.plot_fn_range(f, (start, end), samples)
+    }
+
+    /// Plot `f` at `samples` evenly spaced points from `range.0` to `range.1`
+    pub fn plot_fn_range(
+        &mut self,
+        f: impl Fn(f64) -> f64,
+        range: (f64, f64),
+        samples: usize,
+    ) -> &mut Self {
+        assert!(samples >= 2, "plot_fn needs at least 2 samples");
+        let x_values = linspace(range.0, range.1, samples);
+        let y_values = x_values.iter().map(|&x| f(x)).collect::<Vec<_>>();
+        self.add_series(&x_values, &y_values, SeriesStyle::default())
+    }
+
+    /// Draw a bar from 0 up to each height, centered on its x value and filled with its own color
+    ///
+    /// Bars are 0.8 of the smallest spacing between x values wide, or 0.8 wide if there's only one.
+    pub fn bar_colored(&mut self, x: &[f64], heights: &[f64], colors: &[Color]) -> &mut Self {
+        assert!(
+            x.len() == heights.len() && heights.len() == colors.len(),
+            "x, heights, and colors must be the same length, got {}, {}, and {}",
+            x.len(),
+            heights.len(),
+            colors.len()
+        );
+        let mut sorted = x
+            .iter()
+            .cloned()
+            .filter(|v| v.is_finite())
+            .collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let spacing = sorted
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|&d| d > 0.0)
+            .fold(f64::INFINITY, f64::min);
+        let half_width = 0.4 * if spacing.is_finite() { spacing } else { 1.0 };
+
+        let bars = x
+            .iter()
+            .zip(heights)
+            .zip(colors)
+            .filter(|((x, height), _)| x.is_finite() && height.is_finite())
+            .map(|((&x, &height), &color)| {
+                let (left, right) = (x - half_width, x + half_width);
+                (
+                    vec![left, right, right, left],
+                    vec![0.0, 0.0, height, height],
+                    color,
+                )
+            })
+            .collect();
+        self.add(Item::Polygons {
+            polygons: bars,
+            hatch: self.hatch,
+        })
+    }
+
+    /// Shade the regions between the curve and a horizontal line at `threshold` where the curve is
+    /// above it, or below it when `above` is false
+    ///
+    /// Each region runs between where the curve crosses the threshold, interpolated between
+    /// samples. Points that aren't finite end a region.
+    pub fn fill_threshold(
+        &mut self,
+        x: &[f64],
+        y: &[f64],
+        threshold: f64,
+        above: bool,
+    ) -> &mut Self {
+        assert!(
+            x.len() == y.len(),
+            "x has {} values but y has {}",
+            x.len(),
+            y.len()
+        );
+        let (x, columns) = self.fold(x, &[y]);
+        let y = &columns[0];
+        // How far past the threshold a value is, positive inside the shaded side
+        let depth = |y: f64| if above { y - threshold } else { threshold - y };
+
+        let mut polygons = Vec::new();
+        let mut region: Vec<(f64, f64)> = Vec::new();
+        // Finish a region by dropping back down to the threshold under its last point
+        let mut close = |region: &mut Vec<(f64, f64)>| {
+            if let Some(&(last_x, last_y)) = region.last() {
+                if last_y != threshold {
+                    region.push((last_x, threshold));
+                }
+                let (xs, ys) = region.drain(..).unzip();
+                polygons.push((xs, ys));
+            }
+        };
+
+        let mut previous: Option<(f64, f64)> = None;
+        for (&x, &y) in x.iter().zip(y) {
+            if !(x.is_finite() && y.is_finite()) {
+                close(&mut region);
+                previous = None;
+                continue;
+            }
+            let d = depth(y);
+            match previous {
+                Some((x0, y0)) if (depth(y0) > 0.0) != (d > 0.0) => {
+                    let d0 = depth(y0);
+                    region.push((x0 + (x - x0) * d0 / (d0 - d), threshold));
+                    if d0 > 0.0 {
+                        close(&mut region);
+                    }
+                }
+                None if d > 0.0 => region.push((x, threshold)),
+                _ => {}
+            }
+            if d > 0.0 {
+                region.push((x, y));
+            }
+            previous = Some((x, y));
+        }
+        close(&mut region);
+
+        let color = lighten(self.next_color());
+        self.add(Item::Polygons {
+            polygons: polygons
+                .into_iter()
+                .map(|(xs, ys)| (xs, ys, color))
+                .collect(),
+            hatch: self.hatch,
+        })
+    }
+
+    /// Draw `y` as a line over a lighter band from `y - n_std * sigma` to `y + n_std * sigma`,
+    /// such as a model's prediction with its uncertainty
+    ///
+    /// Points where any of the values isn't finite are left out of the band.
+    pub fn plot_band(&mut self, x: &[f64], y: &[f64], sigma: &[f64], n_std: f64) -> &mut Self {
+        assert!(
+            x.len() == y.len() && y.len() == sigma.len(),
+            "x, y, and sigma must be the same length, got {}, {}, and {}",
+            x.len(),
+            y.len(),
+            sigma.len()
+        );
+        let color = self.next_color();
+        let band_color = lighten(color);
+
+        let (x, columns) = self.fold(x, &[y, sigma]);
+        let (y, sigma) = (&columns[0], &columns[1]);
+        let points = x
+            .iter()
+            .zip(y)
+            .zip(sigma)
+            .map(|((&x, &y), &sigma)| (x, y - n_std * sigma, y + n_std * sigma))
+            .filter(|&(x, lower, upper)| x.is_finite() && lower.is_finite() && upper.is_finite())
+            .collect::<Vec<_>>();
+        // Along the bottom of the band and back along the top
+        let band_x = points
+            .iter()
+            .map(|p| p.0)
+            .chain(points.iter().rev().map(|p| p.0))
+            .collect();
+        let band_y = points
+            .iter()
+            .map(|p| p.1)
+            .chain(points.iter().rev().map(|p| p.2))
+            .collect();
+
+        let zorder = self.zorder;
+        self.add(Item::Polygons {
+            polygons: vec![(band_x, band_y, band_color)],
+            hatch: self.hatch,
+        });
+        self.zorder = zorder;
+        // The line is already folded along with the band
+        let fold = self.phase_fold.take();
+        self.add_series(
+            &x,
+            y,
+            SeriesStyle {
+                color: Some(color),
+                ..SeriesStyle::default()
+            },
+        );
+        self.phase_fold = fold;
+        self
+    }
+
+    /// Draw one entry of a hand-made legend: a short sample of a line and/or marker, with the
+    /// label to its right
+    ///
+    /// `at` is the left end of the sample in points from the bottom left of the figure, inside
+    /// its padding, so entries can go outside the axes. They are drawn before the data.
+    pub fn legend_entry(
+        &mut self,
+        at: Point<f64>,
+        color: Color,
+        marker: Option<Marker>,
+        linestyle: Option<LineStyle>,
+        label: &str,
+    ) -> &mut Self {
+        if let Some(ref linestyle) = linestyle {
+            linestyle.validate();
+        }
+        self.legend_entries.push(LegendEntry {
+            at,
+            color,
+            marker,
+            linestyle,
+            label: label.to_string(),
+        });
+        self
+    }
+
+    /// Draw a short tick just inside the bottom (`Axis2::X`) or left (`Axis2::Y`) side of the frame
+    /// at each value, to show where samples are. Values that aren't finite are skipped, the ticks
+    /// don't affect the limits, and ones outside the limits are clipped.
+    pub fn rug(&mut self, values: &[f64], axis: Axis2) -> &mut Self {
+        self.add(Item::Rug {
+            values: values.iter().cloned().filter(|v| v.is_finite()).collect(),
+            axis,
+        })
+    }
+
+    /// Draw a "//" break glyph across the bottom (`Axis2::X`) or left (`Axis2::Y`) spine at `at`
+    /// in data coordinates
+    ///
+    /// This only draws the glyph; `break_x` actually cuts a range out of the x axis, and draws the
+    /// glyph for it itself.
+    pub fn axis_break(&mut self, axis: Axis2, at: f64) -> &mut Self {
+        self.axis_breaks.push((axis, at));
+        self
+    }
+
+    /// Render the plot into a single-page PDF at `filename`
+    ///
+    /// Everything that has been added, including images and overlays, is drawn on that one page
+    /// over one set of axes. For several figures, call `finish_figure` after each one or use a
+    /// separate `Plot` for each.
+    pub fn write_to<F>(&mut self, filename: F) -> std::io::Result<()>
+    where
+        F: AsRef<std::path::Path>,
+    {
+        let digits = match self.precision {
+            Precision::Digits(digits) => digits,
+            Precision::Adaptive => self.adaptive_precision(),
+        };
+        let mut pdf = Pdf::new();
+        pdf.precision(digits);
+        let mut canvas = PdfCanvas::new(pdf);
+        self.render(&mut canvas);
+        canvas.pdf.write_to(filename)
+    }
+
+    /// Render the figure to PDF bytes and clear it for the next one, keeping all of the styling
+    ///
+    /// Only the data is removed: the plotted items, the legend entries that describe them, the
+    /// fit, and the table. Everything else that has been configured, like the title, axis labels,
+    /// caption, limits, scales and colors, carries over; set them again to change them.
+    ///
+    /// pdfpdf can only write to a file, so each call writes the PDF to a temporary file and reads
+    /// it back, which costs a file creation and deletion per figure on top of `write_to`.
+    /// This is `finish_figure` rather than `finish` because `finish` ends a `begin_series`.
+    pub fn finish_figure(&mut self) -> std::io::Result<Vec<u8>> {
+        // pdfpdf only writes to files, so go through a temporary one that's unique to this call
+        static FIGURES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "pdfplot-{}-{}.pdf",
+            std::process::id(),
+            FIGURES.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        self.write_to(&path)?;
+        let bytes = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+
+        self.items.clear();
+        self.figure += 1;
+        self.legend_entries.clear();
+        self.fit = None;
+        self.table = None;
+        bytes
+    }
+
+    // Enough decimal places to keep the closest pair of neighboring points in any line or set of
+    // markers apart, up to the default of 4
+    fn adaptive_precision(&self) -> u8 {
+        if self.items.is_empty() {
+            return 4;
+        }
+        let frame = self.compute_frame(&self.items);
+        let closest = self
+            .items
+            .iter()
+            .filter_map(|(_, item)| match item {
+                Item::Series(Series {
+                    x_values, y_values, ..
+                })
+                | Item::Markers {
+                    x_values, y_values, ..
+                }
+                | Item::GradientLine {
+                    x_values, y_values, ..
+                } => Some((x_values, y_values)),
+                _ => None,
+            })
+            .flat_map(|(x_values, y_values)| {
+                let points = x_values
+                    .iter()
+                    .zip(y_values)
+                    .filter(|(x, y)| x.is_finite() && y.is_finite())
+                    .map(|(&x, &y)| (frame.to_canvas_x(x), frame.to_canvas_y(y)))
+                    .collect::<Vec<_>>();
+                points
+                    .windows(2)
+                    .map(|pair| (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|&d| d > 0.0)
+            .fold(f64::INFINITY, f64::min);
+        if !closest.is_finite() {
+            return 4;
+        }
+        // A tenth of the closest spacing is well under what anyone can see
+        (1.0 - closest.log10()).ceil().clamp(1.0, 4.0) as u8
+    }
+
+    /// Draw the plot with any backend, such as one that records the calls for a test
+    pub fn render_to<B: Backend>(&mut self, backend: &mut B) {
+        self.render(backend);
+    }
+
+    /// Write the plot as an SVG document instead of a PDF
+    pub fn write_svg_to<F>(&mut self, filename: F) -> std::io::Result<()>
     where
         F: AsRef<std::path::Path>,
     {
-        self.pdf.write_to(filename)
+        let mut svg = Svg::new();
+        self.render(&mut svg);
+        svg.write_to(filename)
+    }
+
+    /// Write the plot to a temporary file and open it in the system's default PDF viewer
+    #[cfg(feature = "show")]
+    pub fn show(&mut self) -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(format!("pdfplot-{}.pdf", std::process::id()));
+        self.write_to(&path)?;
+
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = std::process::Command::new("cmd");
+            command.args(&["/C", "start", ""]);
+            command
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = std::process::Command::new("open");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let mut command = std::process::Command::new("xdg-open");
+
+        let status = command.arg(&path).status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "could not open {}, the viewer exited with {}",
+                    path.display(),
+                    status
+                ),
+            ))
+        }
+    }
+
+    // Draw everything that has been added into a fresh document
+    fn render<B: Backend>(&mut self, canvas: &mut B) {
+        canvas.font(pdfpdf::Font::Helvetica, self.font_size);
+        self.rng = Rng::new(self.jitter_seed);
+
+        if let Some(ref table) = self.table {
+            self.draw_table(canvas, table);
+            return;
+        }
+        if self.items.is_empty() {
+            return;
+        }
+        let items = std::mem::take(&mut self.items);
+        let frame = self.compute_frame(&items);
+
+        canvas
+            .add_page(Size {
+                width: frame.width + 2.0 * self.figure_padding,
+                height: frame.height + 2.0 * self.figure_padding,
+            })
+            .translate(self.figure_padding, self.figure_padding);
+        if self.draw_frame && self.polar {
+            self.draw_polar_grid(canvas, &frame);
+        } else if self.draw_frame {
+            self.draw_axes(canvas, &frame);
+            for &(axis, at) in &self.axis_breaks {
+                self.draw_axis_break(canvas, &frame, axis, at);
+            }
+            if let Some((from, _)) = self.x_break {
+                self.draw_axis_break(canvas, &frame, Axis2::X, from);
+            }
+        }
+
+        for entry in &self.legend_entries {
+            self.draw_legend_entry(canvas, entry);
+        }
+
+        self.clip_to(canvas, &frame);
+        let (bottom, top) = frame.yaxis.limits;
+        if self.zero_line && bottom.min(top) <= 0.0 && 0.0 <= bottom.max(top) {
+            let y = frame.to_canvas_y(0.0);
+            canvas
+                .set_color(Color::gray(160))
+                .set_line_width(0.5)
+                .move_to(Point {
+                    x: frame.to_canvas_x(frame.xaxis.limits.0),
+                    y,
+                })
+                .line_to(Point {
+                    x: frame.to_canvas_x(frame.xaxis.limits.1),
+                    y,
+                })
+                .end_line();
+        }
+        // Sort a list of indices rather than the items themselves, which have to stay in the order
+        // they were added for the series handles into them
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| items[i].0);
+        for i in order {
+            self.draw_item(canvas, &frame, &items[i].1);
+        }
+        // Images reach all the way to the limits, so put back the half of the border they cover
+        let has_image = items.iter().any(|(_, item)| matches!(item, Item::Image(_)));
+        if has_image && self.draw_frame && !self.spines_zero && !self.arrow_spines {
+            canvas.set_color(Color::gray(0)).set_line_width(1.0);
+            self.draw_border(canvas, &frame);
+        }
+        canvas.set_color(Color::gray(0));
+
+        self.items = items;
+    }
+
+    // Axes that run from -r to r, where r is the first radial tick past all the data, with the
+    // radial ticks on both and margins for the angle labels
+    fn polar_axes(&self, items: &[(i32, Item)]) -> (Axis, Axis) {
+        let max_radius = items
+            .iter()
+            .filter_map(|(_, item)| self.extent_of(item))
+            .flat_map(|(x, y)| x.into_iter().zip(y).map(|(x, y)| x.hypot(y)))
+            .filter(|r| r.is_finite())
+            .fold(0.0, f64::max);
+        assert!(
+            max_radius > 0.0,
+            "a polar plot needs some data away from the center"
+        );
+
+        let tick_interval = self
+            .y_tick_interval
+            .unwrap_or_else(|| compute_tick_interval(max_radius));
+        let num_ticks = ((max_radius / tick_interval) - 1e-9).ceil().max(1.0) as usize;
+        let radius = num_ticks as f64 * tick_interval;
+        let mut axis = Axis {
+            limits: (-radius, radius),
+            scale: Scale::Linear,
+            tick_interval,
+            ticks: (1..=num_ticks).map(|i| i as f64 * tick_interval).collect(),
+            tick_labels: Vec::new(),
+            margin: 0.0,
+            break_range: None,
+        };
+        axis.format_tick_labels();
+
+        let (mut xaxis, mut yaxis) = (axis.clone(), axis);
+        xaxis.margin = self.tick_length + self.font_size * 1.5;
+        yaxis.margin = self.tick_length + self.width_of("180") + self.font_size / 2.0;
+        (xaxis, yaxis)
+    }
+
+    // Draw a table on a page of its own size, in place of the plot
+    fn draw_table<B: Backend>(&self, canvas: &mut B, table: &Table) {
+        let pad = self.font_size * 0.4;
+        let row_height = self.font_size * 1.5;
+        let cells = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|v| {
+                        if v.is_nan() {
+                            String::new()
+                        } else {
+                            format!("{:.*}", self.table_precision, v)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let column_widths = table
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(c, header)| {
+                cells
+                    .iter()
+                    .map(|row| self.width_of(&row[c]))
+                    .fold(self.width_in(header, true), f64::max)
+                    + 2.0 * pad
+            })
+            .collect::<Vec<_>>();
+        let width = column_widths.iter().sum::<f64>();
+        let height = (cells.len() + 1) as f64 * row_height;
+        // The right edge of each column
+        let rights = column_widths
+            .iter()
+            .scan(0.0, |right, w| {
+                *right += w;
+                Some(*right)
+            })
+            .collect::<Vec<_>>();
+
+        canvas
+            .add_page(Size {
+                width: width + 2.0 * self.figure_padding,
+                height: height + 2.0 * self.figure_padding,
+            })
+            .translate(self.figure_padding, self.figure_padding);
+
+        // Light rules between the rows and columns, then the outline and the rule under the
+        // headers in black
+        canvas.set_color(Color::gray(200)).set_line_width(0.5);
+        for r in 1..cells.len() {
+            let y = r as f64 * row_height;
+            canvas
+                .move_to(Point { x: 0.0, y })
+                .line_to(Point { x: width, y })
+                .end_line();
+        }
+        for &x in &rights[..rights.len().saturating_sub(1)] {
+            canvas
+                .move_to(Point { x, y: 0.0 })
+                .line_to(Point { x, y: height })
+                .end_line();
+        }
+        canvas
+            .set_color(Color::gray(0))
+            .set_line_width(1.0)
+            .draw_rectangle(Point { x: 0.0, y: 0.0 }, Size { width, height })
+            .move_to(Point {
+                x: 0.0,
+                y: height - row_height,
+            })
+            .line_to(Point {
+                x: width,
+                y: height - row_height,
+            })
+            .end_line();
+
+        // Numbers line up on the right, and so do their headers
+        canvas.font(pdfpdf::Font::HelveticaBold, self.font_size);
+        for (header, &right) in table.headers.iter().zip(&rights) {
+            canvas.draw_text(
+                Point {
+                    x: right - pad,
+                    y: height - row_height / 2.0,
+                },
+                CenterRight,
+                header,
+            );
+        }
+        canvas.font(pdfpdf::Font::Helvetica, self.font_size);
+        for (r, row) in cells.iter().enumerate() {
+            let y = height - (r as f64 + 1.5) * row_height;
+            for (cell, &right) in row.iter().zip(&rights) {
+                canvas.draw_text(Point { x: right - pad, y }, CenterRight, cell);
+            }
+        }
+    }
+
+    // Choose the axes for the items and fit the plot area to the page around them
+    fn compute_frame(&self, items: &[(i32, Item)]) -> Frame {
+        let (xaxis, yaxis) = self.axes_for(items);
+        let image_aspect = if self.polar {
+            // Polar plots need a square plot area to keep their circles round
+            Some(1.0)
+        } else {
+            items.iter().find_map(|(_, item)| match item {
+                Item::Image(image) => Some(image.width as f64 / image.height as f64),
+                _ => None,
+            })
+        };
+        self.layout(xaxis, yaxis, image_aspect)
+    }
+
+    // Polar plots get axes centered on the origin instead of ones fit to the data
+    fn axes_for(&self, items: &[(i32, Item)]) -> (Axis, Axis) {
+        if self.polar {
+            self.polar_axes(items)
+        } else {
+            self.digest_items(items)
+        }
+    }
+
+    // Choose the axes that fit everything that has been added
+    fn digest_items(&self, items: &[(i32, Item)]) -> (Axis, Axis) {
+        let extents = items
+            .iter()
+            .filter_map(|(_, item)| self.extent_of(item))
+            .collect::<Vec<_>>();
+        let data = extents
+            .iter()
+            .map(|(x, y)| (&x[..], &y[..]))
+            .collect::<Vec<_>>();
+        self.digest_tick_settings(&data)
+    }
+
+    /// The x and y axes that the plot would be drawn with right now, for matching the limits and
+    /// ticks of another figure
+    ///
+    /// Panics, as writing would, if there's no data and no limits have been set.
+    pub fn resolved_axes(&self) -> (Axis, Axis) {
+        self.axes_for(&self.items)
+    }
+
+    // The points that an item needs to fit inside the autoscaled limits
+    fn extent_of(&self, item: &Item) -> Option<(Vec<f64>, Vec<f64>)> {
+        match item {
+            Item::Series(Series {
+                x_values, y_values, ..
+            })
+            | Item::Markers {
+                x_values, y_values, ..
+            }
+            | Item::GradientLine {
+                x_values, y_values, ..
+            } => Some((x_values.clone(), y_values.clone())),
+            // The bars, not the points, set how far the data reaches
+            Item::ErrorBars {
+                x_values,
+                lower,
+                upper,
+                ..
+            } => Some((
+                x_values.iter().chain(x_values).cloned().collect(),
+                lower.iter().chain(upper).cloned().collect(),
+            )),
+            Item::Segments { segments, .. } => Some(
+                segments
+                    .iter()
+                    .flat_map(|&(start, end)| vec![start, end])
+                    .unzip(),
+            ),
+            Item::Image(image) => {
+                let (x_range, y_range) = self.image_extent(image);
+                Some((vec![x_range.0, x_range.1], vec![y_range.0, y_range.1]))
+            }
+            Item::Polygons { polygons, .. } => Some((
+                polygons.iter().flat_map(|p| p.0.clone()).collect(),
+                polygons.iter().flat_map(|p| p.1.clone()).collect(),
+            )),
+            Item::Line { .. }
+            | Item::VLine { .. }
+            | Item::VSpans { .. }
+            | Item::Rug { .. }
+            | Item::Highlight { .. } => None,
+        }
+    }
+
+    // Where an image sits in data coordinates: its own extent, or the limits, or its pixels
+    fn image_extent(&self, image: &Image) -> ((f64, f64), (f64, f64)) {
+        (
+            image
+                .extent
+                .map(|e| e.0)
+                .or(self.xlim)
+                .unwrap_or((0.0, image.width as f64)),
+            image
+                .extent
+                .map(|e| e.1)
+                .or(self.ylim)
+                .unwrap_or((0.0, image.height as f64)),
+        )
+    }
+
+    fn draw_item<B: Backend>(&mut self, canvas: &mut B, frame: &Frame, item: &Item) {
+        let to_canvas_x = |x| frame.to_canvas_x(x);
+        let to_canvas_y = |y| frame.to_canvas_y(y);
+
+        match item {
+            Item::Series(s) => {
+                let color = s.style.color.unwrap_or(self.colors[0]);
+                canvas
+                    .set_color(color)
+                    .set_line_width(s.style.line_width.unwrap_or(1.5));
+                let linestyle = s
+                    .style
+                    .linestyle
+                    .as_ref()
+                    .or(self.linestyle.as_ref())
+                    .filter(|style| !matches!(style, LineStyle::None));
+                if let Some(linestyle) = linestyle {
+                    // Match the clipping box, which sits just outside the border
+                    let pad = self.clip_padding;
+                    let bounds = if self.drop_clipped {
+                        let (left, right) = (
+                            to_canvas_x(frame.xaxis.limits.0),
+                            to_canvas_x(frame.xaxis.limits.1),
+                        );
+                        let (bottom, top) = (
+                            to_canvas_y(frame.yaxis.limits.0),
+                            to_canvas_y(frame.yaxis.limits.1),
+                        );
+                        Some((
+                            (left.min(right) - pad, left.max(right) + pad),
+                            (bottom.min(top) - pad, bottom.max(top) + pad),
+                        ))
+                    } else {
+                        None
+                    };
+                    let points = s
+                        .x_values
+                        .iter()
+                        .zip(&s.y_values)
+                        .map(|(&x, &y)| (to_canvas_x(x), to_canvas_y(y)));
+                    for mut run in split_line(points, bounds) {
+                        if self.simplify_tolerance > 0.0 {
+                            run = simplify(&run, self.simplify_tolerance);
+                        }
+                        let dashes = match linestyle.dashes() {
+                            Some(pattern) => dash_line(&run, pattern),
+                            None => vec![run],
+                        };
+                        for dash in dashes {
+                            canvas.draw_line(dash.iter().map(|p| p.0), dash.iter().map(|p| p.1));
+                        }
+                    }
+                }
+                let has_line = linestyle.is_some();
+                if let Some(marker) = s.style.marker.or(self.marker) {
+                    // Only scatter points are jittered, since markers on a line belong on it
+                    if has_line || self.jitter == 0.0 {
+                        self.draw_markers(canvas, frame, marker, color, &s.x_values, &s.y_values);
+                    } else {
+                        let x_values = s
+                            .x_values
+                            .iter()
+                            .map(|&x| x + self.jitter * (2.0 * self.rng.next_f64() - 1.0))
+                            .collect::<Vec<_>>();
+                        self.draw_markers(canvas, frame, marker, color, &x_values, &s.y_values);
+                    }
+                }
+
+                // Endpoints go over the series' own markers so that they stay visible
+                if has_line {
+                    let mut points = s
+                        .x_values
+                        .iter()
+                        .zip(&s.y_values)
+                        .filter(|(x, y)| x.is_finite() && y.is_finite());
+                    let first = points.next();
+                    let last = points.next_back().or(first);
+                    for (point, end) in [first, last]
+                        .iter()
+                        .zip(&[self.start_marker, self.end_marker])
+                    {
+                        if let (Some((&x, &y)), Some((marker, color))) = (point, end) {
+                            canvas.set_color(*color);
+                            self.draw_marker(canvas, frame, *marker, x, y);
+                        }
+                    }
+                }
+            }
+            Item::Markers {
+                x_values,
+                y_values,
+                color,
+            } => {
+                let marker = self.marker.unwrap_or(Marker::Dot);
+                self.draw_markers(canvas, frame, marker, *color, x_values, y_values);
+            }
+            Item::GradientLine {
+                x_values,
+                y_values,
+                colors,
+            } => {
+                canvas.set_line_width(1.5);
+                let points = x_values.iter().zip(y_values);
+                for (((&x0, &y0), (&x1, &y1)), &color) in
+                    points.clone().zip(points.skip(1)).zip(colors)
+                {
+                    if !(x0.is_finite() && y0.is_finite() && x1.is_finite() && y1.is_finite()) {
+                        continue;
+                    }
+                    canvas
+                        .set_color(color)
+                        .move_to(Point {
+                            x: to_canvas_x(x0),
+                            y: to_canvas_y(y0),
+                        })
+                        .line_to(Point {
+                            x: to_canvas_x(x1),
+                            y: to_canvas_y(y1),
+                        })
+                        .end_line();
+                }
+            }
+            Item::ErrorBars {
+                x_values,
+                y_values,
+                lower,
+                upper,
+                color,
+            } => {
+                canvas.set_color(*color).set_line_width(1.0);
+                for ((&x, &lower), &upper) in x_values.iter().zip(lower).zip(upper) {
+                    let x = to_canvas_x(x);
+                    let (lower, upper) = (to_canvas_y(lower), to_canvas_y(upper));
+                    let cap = MARKER_SIZE / 2.0;
+                    canvas
+                        .move_to(Point { x, y: lower })
+                        .line_to(Point { x, y: upper })
+                        .end_line();
+                    for &y in &[lower, upper] {
+                        canvas
+                            .move_to(Point { x: x - cap, y })
+                            .line_to(Point { x: x + cap, y })
+                            .end_line();
+                    }
+                }
+                let marker = self.marker.unwrap_or(Marker::Dot);
+                self.draw_markers(canvas, frame, marker, *color, x_values, y_values);
+            }
+            Item::Segments { segments, color } => {
+                canvas.set_line_width(1.0).set_color(*color);
+                for &((x0, y0), (x1, y1)) in segments {
+                    canvas
+                        .move_to(Point {
+                            x: to_canvas_x(x0),
+                            y: to_canvas_y(y0),
+                        })
+                        .line_to(Point {
+                            x: to_canvas_x(x1),
+                            y: to_canvas_y(y1),
+                        })
+                        .end_line();
+                }
+            }
+            Item::Line {
+                slope,
+                intercept,
+                color,
+            } => {
+                let (start, end) = frame.xaxis.limits;
+                canvas
+                    .set_color(*color)
+                    .set_line_width(1.5)
+                    .move_to(Point {
+                        x: to_canvas_x(start),
+                        y: to_canvas_y(slope * start + intercept),
+                    })
+                    .line_to(Point {
+                        x: to_canvas_x(end),
+                        y: to_canvas_y(slope * end + intercept),
+                    })
+                    .end_line();
+            }
+            Item::Image(image) => {
+                let (x_range, y_range) = self.image_extent(image);
+                let x_extent = to_canvas_x(x_range.1) - to_canvas_x(x_range.0);
+                let y_extent = to_canvas_y(y_range.1) - to_canvas_y(y_range.0);
+                canvas.draw_image(
+                    &image.png_bytes,
+                    image.width,
+                    image.height,
+                    Point {
+                        x: to_canvas_x(x_range.0),
+                        y: to_canvas_y(y_range.0),
+                    },
+                    Size {
+                        width: x_extent,
+                        height: y_extent,
+                    },
+                );
+            }
+            Item::Rug { values, axis } => {
+                // Reversed limits put the first limit on the right or top, so take whichever
+                // side is on the left and bottom of the page
+                let left = to_canvas_x(frame.xaxis.limits.0).min(to_canvas_x(frame.xaxis.limits.1));
+                let bottom =
+                    to_canvas_y(frame.yaxis.limits.0).min(to_canvas_y(frame.yaxis.limits.1));
+                canvas.set_color(Color::gray(0)).set_line_width(1.0);
+                for &v in values {
+                    let (start, end) = match axis {
+                        Axis2::X => {
+                            let x = to_canvas_x(v);
+                            ((x, bottom), (x, bottom + frame.plot_height * 0.03))
+                        }
+                        Axis2::Y => {
+                            let y = to_canvas_y(v);
+                            ((left, y), (left + frame.plot_height * 0.03, y))
+                        }
+                    };
+                    canvas
+                        .move_to(Point {
+                            x: start.0,
+                            y: start.1,
+                        })
+                        .line_to(Point { x: end.0, y: end.1 })
+                        .end_line();
+                }
+            }
+            Item::VLine { x, label } => {
+                let x = to_canvas_x(*x);
+                let bottom = to_canvas_y(frame.yaxis.limits.0);
+                let top = to_canvas_y(frame.yaxis.limits.1);
+
+                canvas.set_color(Color::gray(0)).set_line_width(1.0);
+                let pattern = LineStyle::Dashed.dashes().unwrap();
+                for dash in dash_line(&[(x, bottom), (x, top)], pattern) {
+                    canvas.draw_line(dash.iter().map(|p| p.0), dash.iter().map(|p| p.1));
+                }
+                canvas.draw_text(
+                    Point {
+                        x: x + 3.0,
+                        y: top - 3.0,
+                    },
+                    TopLeft,
+                    label,
+                );
+            }
+            Item::Polygons { polygons, hatch } => {
+                canvas.set_line_width(0.5);
+                for (xs, ys, color) in polygons {
+                    let xs = xs.iter().map(|&x| to_canvas_x(x)).collect::<Vec<_>>();
+                    let ys = ys.iter().map(|&y| to_canvas_y(y)).collect::<Vec<_>>();
+                    canvas.set_color(*color);
+                    fill_region(canvas, *hatch, xs, ys);
+                }
+            }
+            Item::VSpans { intervals, hatch } => {
+                let (xmin, xmax) = frame.xaxis.limits;
+                let (xmin, xmax) = (xmin.min(xmax), xmin.max(xmax));
+                let bottom = to_canvas_y(frame.yaxis.limits.0);
+                let top = to_canvas_y(frame.yaxis.limits.1);
+
+                canvas.set_color(Color::gray(220)).set_line_width(0.5);
+                for &(start, end) in intervals {
+                    // Clip each band to the plot area
+                    let left = to_canvas_x(start.max(xmin).min(xmax));
+                    let right = to_canvas_x(end.max(xmin).min(xmax));
+                    fill_region(
+                        canvas,
+                        *hatch,
+                        vec![left, right, right, left],
+                        vec![bottom, bottom, top, top],
+                    );
+                }
+            }
+            Item::Highlight {
+                start,
+                end,
+                label,
+                color,
+                hatch,
+            } => {
+                let (xmin, xmax) = frame.xaxis.limits;
+                let (xmin, xmax) = (xmin.min(xmax), xmin.max(xmax));
+                if *end < xmin || *start > xmax {
+                    return;
+                }
+                let (left, right) = (
+                    to_canvas_x(start.max(xmin).min(xmax)),
+                    to_canvas_x(end.max(xmin).min(xmax)),
+                );
+                let (left, right) = (left.min(right), left.max(right));
+                let bottom = to_canvas_y(frame.yaxis.limits.0);
+                let top = to_canvas_y(frame.yaxis.limits.1);
+                canvas.set_color(*color).set_line_width(0.5);
+                fill_region(
+                    canvas,
+                    *hatch,
+                    vec![left, right, right, left],
+                    vec![bottom, bottom, top, top],
+                );
+
+                // Keep the label inside the plot area even when the band is at its edge
+                let (plot_left, plot_right) = (to_canvas_x(xmin), to_canvas_x(xmax));
+                let (plot_left, plot_right) =
+                    (plot_left.min(plot_right), plot_left.max(plot_right));
+                let half_width = self.width_of(label) / 2.0 + 3.0;
+                let x = ((left + right) / 2.0)
+                    .min(plot_right - half_width)
+                    .max(plot_left + half_width);
+                canvas.set_color(Color::gray(0)).draw_text(
+                    Point { x, y: top - 3.0 },
+                    TopCenter,
+                    label,
+                );
+            }
+        }
+    }
+
+    /// Size the plot area to fit the page around the margins that the axes need
+    ///
+    /// Plots with an image or a data aspect get a constrained plot area, and the page shrinks to
+    /// fit it.
+    fn layout(&self, mut xaxis: Axis, mut yaxis: Axis, image_aspect: Option<f64>) -> Frame {
+        // Everything is drawn inside the padding
+        let width = self.width - 2.0 * self.figure_padding;
+        let height = self.height - 2.0 * self.figure_padding;
+
+        // The plot area's height over its width that makes the data aspect come out right
+        let data_shape = self.data_aspect.map(|ratio| {
+            let x_range = xaxis.position(xaxis.limits.1) - xaxis.position(xaxis.limits.0);
+            let y_range = yaxis.position(yaxis.limits.1) - yaxis.position(yaxis.limits.0);
+            ratio * (y_range / x_range).abs()
+        });
+
+        if !self.draw_frame {
+            // Without axes the plot area is the whole page, cut down to the aspect of the image
+            xaxis.margin = 0.0;
+            yaxis.margin = 0.0;
+            let (width, height) = match data_shape.map(|shape| 1.0 / shape).or(image_aspect) {
+                Some(aspect) if width / height > aspect => (height * aspect, height),
+                Some(aspect) => (width, width / aspect),
+                None => (width, height),
+            };
+            return Frame {
+                xaxis,
+                yaxis,
+                plot_width: width,
+                plot_height: height,
+                width,
+                height,
+            };
+        }
+
+        let plot_width =
+            width - yaxis.margin - xaxis.tick_labels.last().map_or(0.0, |l| self.width_of(l));
+        let plot_height = height - xaxis.margin - self.top_margin();
+        if let Some(shape) = data_shape.or_else(|| image_aspect.map(|_| 1.0)) {
+            let (plot_width, plot_height) = if plot_height / plot_width > shape {
+                (plot_width, plot_width * shape)
+            } else {
+                (plot_height / shape, plot_height)
+            };
+            // A narrower page can wrap the caption onto more lines, which the page grows to fit
+            let frame_width = plot_width + yaxis.margin + self.font_size;
+            xaxis.margin += self.caption_height(frame_width) - self.caption_height(width);
+            return Frame {
+                width: frame_width,
+                height: plot_height + xaxis.margin + self.top_margin(),
+                xaxis,
+                yaxis,
+                plot_width,
+                plot_height,
+            };
+        }
+        Frame {
+            xaxis,
+            yaxis,
+            plot_width,
+            plot_height,
+            width,
+            height,
+        }
+    }
+
+    // Clip data to just outside the border so that lines along the limits are still visible
+    fn clip_to<B: Backend>(&self, canvas: &mut B, frame: &Frame) {
+        let to_canvas_x = |x| frame.to_canvas_x(x);
+        let to_canvas_y = |y| frame.to_canvas_y(y);
+        let xaxis = &frame.xaxis;
+        let yaxis = &frame.yaxis;
+        let pad = self.clip_padding;
+        canvas.set_clipping_box(
+            Point {
+                x: to_canvas_x(xaxis.limits.0) - pad,
+                y: to_canvas_y(yaxis.limits.0) - pad,
+            },
+            Size {
+                width: to_canvas_x(xaxis.limits.1) - to_canvas_x(xaxis.limits.0) + 2.0 * pad,
+                height: to_canvas_y(yaxis.limits.1) - to_canvas_y(yaxis.limits.0) + 2.0 * pad,
+            },
+        );
+    }
+
+    // The color of a marker at height `y` in a series drawn in `color`
+    fn marker_color(&self, y: f64, color: Color) -> Color {
+        match self.threshold_color {
+            Some((threshold, above)) if y > threshold => above,
+            _ => color,
+        }
+    }
+
+    fn draw_markers<B: Backend>(
+        &self,
+        canvas: &mut B,
+        frame: &Frame,
+        marker: Marker,
+        color: Color,
+        x_values: &[f64],
+        y_values: &[f64],
+    ) {
+        if !self.marker_filled {
+            canvas.set_line_width(1.0);
+        }
+        for (&x, &y) in x_values
+            .iter()
+            .zip(y_values)
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+        {
+            canvas.set_color(self.marker_color(y, color));
+            self.draw_marker(canvas, frame, marker, x, y);
+        }
+    }
+
+    // Draw a single marker centered on a point in data coordinates
+    fn draw_marker<B: Backend>(
+        &self,
+        canvas: &mut B,
+        frame: &Frame,
+        marker: Marker,
+        x: f64,
+        y: f64,
+    ) {
+        let half = self.marker_size / 2.0;
+        // Data-sized markers are stretched by the scale of each axis where they're drawn
+        let (x_radius, y_radius) = match self.marker_size_units {
+            Units::Points => (half, half),
+            Units::Data => (
+                (frame.to_canvas_x(x + half) - frame.to_canvas_x(x - half)).abs() / 2.0,
+                (frame.to_canvas_y(y + half) - frame.to_canvas_y(y - half)).abs() / 2.0,
+            ),
+        };
+        self.draw_marker_at(
+            canvas,
+            marker,
+            frame.to_canvas_x(x),
+            frame.to_canvas_y(y),
+            (x_radius, y_radius),
+        );
+    }
+
+    // Draw a single marker centered on a point in canvas coordinates, with radii in points
+    fn draw_marker_at<B: Backend>(
+        &self,
+        canvas: &mut B,
+        marker: Marker,
+        mut x: f64,
+        mut y: f64,
+        (x_radius, y_radius): (f64, f64),
+    ) {
+        if self.crisp_markers {
+            x = x.floor() + 0.5;
+            y = y.floor() + 0.5;
+        }
+        // Every marker is a polygon, given as angles around the center
+        let angles = match marker {
+            Marker::Dot => (0..16).map(|i| i as f64 * 22.5).collect::<Vec<_>>(),
+            Marker::Square => vec![45.0, 135.0, 225.0, 315.0],
+            Marker::Triangle => vec![90.0, 210.0, 330.0],
+        };
+        // Squares reach their corners, so shrink them to look the same size as a dot
+        let stretch = match marker {
+            Marker::Square => 0.9 * std::f64::consts::SQRT_2,
+            _ => 1.0,
+        };
+        let x_values = angles
+            .iter()
+            .map(|a: &f64| x + x_radius * stretch * a.to_radians().cos());
+        let y_values = angles
+            .iter()
+            .map(|a: &f64| y + y_radius * stretch * a.to_radians().sin());
+        if self.marker_filled {
+            canvas.fill_polygon(x_values, y_values);
+        } else {
+            // Repeat the first point to close the outline
+            canvas.draw_line(
+                x_values.clone().chain(x_values.take(1)),
+                y_values.clone().chain(y_values.take(1)),
+            );
+        }
+    }
+
+    fn draw_legend_entry<B: Backend>(&self, canvas: &mut B, entry: &LegendEntry) {
+        let Point { x, y } = entry.at;
+        canvas.set_color(entry.color);
+        if let Some(linestyle) = entry
+            .linestyle
+            .as_ref()
+            .filter(|style| !matches!(style, LineStyle::None))
+        {
+            canvas.set_line_width(1.5);
+            let sample = [(x, y), (x + LEGEND_SAMPLE_LENGTH, y)];
+            let dashes = match linestyle.dashes() {
+                Some(pattern) => dash_line(&sample, pattern),
+                None => vec![sample.to_vec()],
+            };
+            for dash in dashes {
+                canvas.draw_line(dash.iter().map(|p| p.0), dash.iter().map(|p| p.1));
+            }
+        }
+        if let Some(marker) = entry.marker {
+            if !self.marker_filled {
+                canvas.set_line_width(1.0);
+            }
+            // There's no data scale out here, so data-sized markers get the default size
+            let radius = match self.marker_size_units {
+                Units::Points => self.marker_size / 2.0,
+                Units::Data => MARKER_SIZE / 2.0,
+            };
+            self.draw_marker_at(
+                canvas,
+                marker,
+                x + LEGEND_SAMPLE_LENGTH / 2.0,
+                y,
+                (radius, radius),
+            );
+        }
+        canvas.set_color(Color::gray(0)).draw_text(
+            Point {
+                x: x + LEGEND_SAMPLE_LENGTH + self.font_size / 2.0,
+                y,
+            },
+            CenterLeft,
+            &entry.label,
+        );
+    }
+
+    fn draw_axis_break<B: Backend>(&self, canvas: &mut B, frame: &Frame, axis: Axis2, at: f64) {
+        // The center of the glyph, the direction along the spine, and the direction across it
+        let ((x, y), (along_x, along_y), (across_x, across_y)) = match axis {
+            Axis2::X => (
+                (
+                    frame.to_canvas_x(at),
+                    frame.to_canvas_y(frame.yaxis.limits.0),
+                ),
+                (1.0, 0.0),
+                (0.0, 1.0),
+            ),
+            Axis2::Y => (
+                (
+                    frame.to_canvas_x(frame.xaxis.limits.0),
+                    frame.to_canvas_y(at),
+                ),
+                (0.0, 1.0),
+                (1.0, 0.0),
+            ),
+        };
+        let reach = self.tick_length;
+        let gap = self.tick_length / 2.0;
+
+        // Each stroke of the glyph is a diagonal from one side of the spine to the other
+        let stroke = |offset: f64| {
+            (
+                Point {
+                    x: x + along_x * (offset - reach / 2.0) - across_x * reach,
+                    y: y + along_y * (offset - reach / 2.0) - across_y * reach,
+                },
+                Point {
+                    x: x + along_x * (offset + reach / 2.0) + across_x * reach,
+                    y: y + along_y * (offset + reach / 2.0) + across_y * reach,
+                },
+            )
+        };
+        let (first_start, first_end) = stroke(-gap / 2.0);
+        let (second_start, second_end) = stroke(gap / 2.0);
+
+        // Erase the spine between the strokes, then draw them
+        canvas
+            .set_color(Color::gray(255))
+            .fill_polygon(
+                vec![first_start.x, second_start.x, second_end.x, first_end.x],
+                vec![first_start.y, second_start.y, second_end.y, first_end.y],
+            )
+            .set_color(Color::gray(0))
+            .set_line_width(1.0)
+            .move_to(first_start)
+            .line_to(first_end)
+            .end_line()
+            .move_to(second_start)
+            .line_to(second_end)
+            .end_line();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The points of every line stroked in one call, in canvas coordinates
+    fn lines(recording: &Recording) -> Vec<&Vec<Point<f64>>> {
+        recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::DrawLine(points) => Some(points),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn rgb(color: Color) -> (u8, u8, u8) {
+        (color.red, color.green, color.blue)
+    }
+
+    #[test]
+    fn threshold_color_marks_points_above() {
+        let red = Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+        let series = COLOR_CYCLE[0];
+        let mut plot = Plot::new();
+        assert_eq!(rgb(plot.marker_color(5.0, series)), rgb(series));
+
+        // A point at the threshold isn't above it
+        plot.threshold_color(3.0, red);
+        let colors = [1.0, 5.0, 3.0]
+            .iter()
+            .map(|&y| rgb(plot.marker_color(y, series)))
+            .collect::<Vec<_>>();
+        assert_eq!(colors, vec![rgb(series), rgb(red), rgb(series)]);
+    }
+
+    #[test]
+    fn constant_image_is_drawn_in_the_low_color() {
+        assert_eq!(colormap_position(2.5, (2.5, 2.5)), 0.0);
+        assert_eq!(colormap_position(1.0, (1.0, 3.0)), 0.0);
+        assert_eq!(colormap_position(2.0, (1.0, 3.0)), 127.5);
+        assert_eq!(colormap_position(3.0, (1.0, 3.0)), 255.0);
+    }
+
+    #[test]
+    fn image_and_series_share_one_page() {
+        let mut plot = Plot::new();
+        plot.plot(&[0.0, 1.0], &[0.0, 1.0])
+            .image(&[0.0, 1.0, 2.0, 3.0], 2, 2)
+            .plot(&[0.0, 1.0], &[1.0, 0.0]);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        let pages = recording
+            .calls
+            .iter()
+            .filter(|call| matches!(call, DrawCall::AddPage(_)))
+            .count();
+        assert_eq!(pages, 1);
+    }
+
+    #[test]
+    fn regression_skips_the_fit_for_a_vertical_line() {
+        let mut plot = Plot::new();
+        plot.regression(&[2.0, 2.0, 2.0], &[1.0, 2.0, 3.0]);
+        assert_eq!(plot.regression_fit(), None);
+        assert!(!plot
+            .items
+            .iter()
+            .any(|(_, item)| matches!(item, Item::Line { .. })));
+
+        plot.regression(&[0.0, 1.0, 2.0], &[1.0, 3.0, 5.0]);
+        assert_eq!(plot.regression_fit(), Some((2.0, 1.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "linthresh must be positive")]
+    fn symlog_rejects_a_zero_linthresh() {
+        Plot::new().yscale(Scale::SymLog { linthresh: 0.0 });
+    }
+
+    #[test]
+    #[should_panic(expected = "bullseye radii must be positive")]
+    fn shaded_bullseye_rejects_nan_radii() {
+        Plot::new().bullseye_shaded((0.0, 0.0), &[1.0, f64::NAN]);
+    }
+
+    #[test]
+    fn frameless_image_fills_the_page() {
+        let data = (0..21).map(|v| v as f64).collect::<Vec<_>>();
+        let mut plot = Plot::new();
+        plot.frame(false).image(&data, 7, 3);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        let page = recording.calls.iter().find_map(|call| match call {
+            DrawCall::AddPage(size) => Some(*size),
+            _ => None,
+        });
+        let image = recording.calls.iter().find_map(|call| match call {
+            DrawCall::DrawImage { size, .. } => Some(*size),
+            _ => None,
+        });
+        let (page, image) = (page.unwrap(), image.unwrap());
+        assert!((page.width - image.width).abs() < 1e-9);
+        assert!((page.height - image.height).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fill_threshold_splits_at_interpolated_crossings() {
+        let mut plot = Plot::new();
+        plot.fill_threshold(
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            &[0.0, 2.0, 0.0, 3.0, 0.0],
+            1.0,
+            true,
+        );
+        let frame = plot.compute_frame(&plot.items);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+
+        let polygons = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::FillPolygon(points) => Some(points),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(polygons.len(), 2);
+        let crossings = [(0.5, 1.5), (2.0 + 1.0 / 3.0, 3.0 + 2.0 / 3.0)];
+        for (polygon, &(rise, fall)) in polygons.iter().zip(&crossings) {
+            let (first, last) = (polygon[0], polygon[polygon.len() - 1]);
+            for (point, x) in [(first, rise), (last, fall)] {
+                assert!((point.x - frame.to_canvas_x(x)).abs() < 1e-9);
+                assert!((point.y - frame.to_canvas_y(1.0)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn errorbars_skip_non_finite_entries() {
+        let mut plot = Plot::new();
+        plot.errorbar_asym(
+            &[0.0, 1.0, f64::NAN, 3.0, 4.0],
+            &[1.0, f64::INFINITY, 2.0, 3.0, 2.0],
+            &[0.5, 0.5, 0.5, f64::NAN, 0.5],
+            &[0.5, 0.5, 0.5, 0.5, 1.0],
+        );
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        for call in &recording.calls {
+            if let DrawCall::MoveTo(point) | DrawCall::LineTo(point) = call {
+                assert!(point.x.is_finite() && point.y.is_finite());
+            }
+        }
+        match &plot.items[0].1 {
+            Item::ErrorBars {
+                x_values, upper, ..
+            } => {
+                assert_eq!(x_values, &[0.0, 4.0]);
+                assert_eq!(upper, &[1.5, 3.0]);
+            }
+            _ => panic!("errorbar_asym should add error bars"),
+        }
+    }
+
+    #[test]
+    fn tick_format_fills_in_the_placeholder() {
+        assert_eq!(TickFormat::parse("{:.1f}").format(2.25), "2.2");
+        assert_eq!(TickFormat::parse("{:.2}").format(3.0), "3.00");
+        assert_eq!(TickFormat::parse("{:e}").format(1500.0), "1.5e3");
+        assert_eq!(TickFormat::parse("{:.1e}").format(1500.0), "1.5e3");
+        assert_eq!(TickFormat::parse("{}").format(0.5), "0.5");
+        assert_eq!(TickFormat::parse("t = {:.0} s").format(4.0), "t = 4 s");
+        assert_eq!(TickFormat::parse("{{{}}}%").format(7.0), "{7}%");
+    }
+
+    #[test]
+    #[should_panic(expected = "has no placeholder")]
+    fn tick_format_needs_a_placeholder() {
+        TickFormat::parse("volts");
+    }
+
+    #[test]
+    #[should_panic(expected = "more than one placeholder")]
+    fn tick_format_takes_one_placeholder() {
+        TickFormat::parse("{} to {}");
+    }
+
+    #[test]
+    #[should_panic(expected = "unclosed")]
+    fn tick_format_rejects_an_unclosed_brace() {
+        TickFormat::parse("{:.2");
+    }
+
+    #[test]
+    #[should_panic(expected = "unmatched")]
+    fn tick_format_rejects_a_stray_closing_brace() {
+        TickFormat::parse("{} }");
+    }
+
+    #[test]
+    #[should_panic(expected = "placeholder that isn't")]
+    fn tick_format_rejects_other_specs() {
+        TickFormat::parse("{:>8}");
+    }
+
+    #[test]
+    fn clip_segment_cuts_at_the_box() {
+        let bounds = ((0.0, 2.0), (0.0, 2.0));
+        // Crossing the whole box cuts both ends
+        assert_eq!(
+            clip_segment((-1.0, 1.0), (3.0, 1.0), bounds),
+            Some(((0.0, 1.0), (2.0, 1.0)))
+        );
+        // An end inside the box is kept as it is
+        assert_eq!(
+            clip_segment((1.0, 1.0), (1.0, 4.0), bounds),
+            Some(((1.0, 1.0), (1.0, 2.0)))
+        );
+        // Entirely outside, whether parallel to an edge or not
+        assert_eq!(clip_segment((-1.0, 3.0), (3.0, 3.0), bounds), None);
+        assert_eq!(clip_segment((-2.0, 1.0), (1.0, 4.0), bounds), None);
+        // Touching only the corner leaves just that point
+        assert_eq!(
+            clip_segment((-1.0, 1.0), (1.0, -1.0), bounds),
+            Some(((0.0, 0.0), (0.0, 0.0)))
+        );
+    }
+
+    #[test]
+    fn split_line_breaks_at_gaps_and_the_box() {
+        let points = [
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (f64::NAN, 1.0),
+            (1.0, 0.5),
+            (3.0, 0.5),
+            (1.0, 1.5),
+        ];
+        assert_eq!(
+            split_line(points.iter().cloned(), None),
+            vec![
+                vec![(0.0, 1.0), (1.0, 1.0)],
+                vec![(1.0, 0.5), (3.0, 0.5), (1.0, 1.5)],
+            ]
+        );
+        // Leaving the box ends a run, and coming back in starts a new one at the edge
+        assert_eq!(
+            split_line(points.iter().cloned(), Some(((0.0, 2.0), (0.0, 2.0)))),
+            vec![
+                vec![(0.0, 1.0), (1.0, 1.0)],
+                vec![(1.0, 0.5), (2.0, 0.5)],
+                vec![(2.0, 1.0), (1.0, 1.5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn simplify_collapses_collinear_runs() {
+        let line = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)];
+        assert_eq!(simplify(&line, 0.1), vec![(0.0, 0.0), (3.0, 3.0)]);
+
+        // A corner farther off than the tolerance stays, and the runs on either side of it
+        // still collapse
+        let corner = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (2.0, 2.0)];
+        assert_eq!(
+            simplify(&corner, 0.1),
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)]
+        );
+        // A bump smaller than the tolerance doesn't
+        let bump = [(0.0, 0.0), (1.0, 0.05), (2.0, 0.0)];
+        assert_eq!(simplify(&bump, 0.1), vec![(0.0, 0.0), (2.0, 0.0)]);
+    }
+
+    #[test]
+    fn dash_line_follows_the_pattern() {
+        fn assert_dashes(points: &[(f64, f64)], style: LineStyle, expected: &[&[(f64, f64)]]) {
+            let dashes = dash_line(points, style.dashes().unwrap());
+            assert_eq!(dashes.len(), expected.len(), "{:?}", dashes);
+            for (dash, expected) in dashes.iter().zip(expected) {
+                assert_eq!(dash.len(), expected.len(), "{:?}", dashes);
+                for (a, b) in dash.iter().zip(expected.iter()) {
+                    let close = (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+                    assert!(close, "{:?}", dashes);
+                }
+            }
+        }
+
+        let line = [(0.0, 0.0), (20.0, 0.0)];
+        assert_dashes(
+            &line,
+            LineStyle::Dashed,
+            &[&[(0.0, 0.0), (6.0, 0.0)], &[(10.0, 0.0), (16.0, 0.0)]],
+        );
+        assert_dashes(
+            &line,
+            LineStyle::Dotted,
+            &[
+                &[(0.0, 0.0), (1.5, 0.0)],
+                &[(4.5, 0.0), (6.0, 0.0)],
+                &[(9.0, 0.0), (10.5, 0.0)],
+                &[(13.5, 0.0), (15.0, 0.0)],
+                &[(18.0, 0.0), (19.5, 0.0)],
+            ],
+        );
+        // An odd-length pattern swaps dashes and gaps each time it repeats
+        assert_dashes(
+            &line,
+            LineStyle::Custom(vec![5.0, 2.0, 3.0]),
+            &[
+                &[(0.0, 0.0), (5.0, 0.0)],
+                &[(7.0, 0.0), (10.0, 0.0)],
+                &[(15.0, 0.0), (17.0, 0.0)],
+            ],
+        );
+        // A dash that reaches a corner turns it, keeping the corner
+        assert_dashes(
+            &[(0.0, 0.0), (4.0, 0.0), (4.0, 10.0)],
+            LineStyle::Dashed,
+            &[
+                &[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0)],
+                &[(4.0, 6.0), (4.0, 10.0)],
+            ],
+        );
+    }
+
+    #[test]
+    fn xlim_percentile_leaves_out_outliers() {
+        let mut x = (0..100).map(|v| v as f64).collect::<Vec<_>>();
+        x.push(1000.0);
+        let mut plot = Plot::new();
+        plot.tick_snap(false)
+            .xlim_percentile(0.0, 99.0)
+            .plot(&x, &x);
+        assert_eq!(plot.resolved_axes().0.limits(), (0.0, 99.0));
+
+        // Without any finite x values there's nothing to take a percentile of, which is only an
+        // error if the limits aren't set some other way
+        let mut plot = Plot::new();
+        plot.xlim_percentile(5.0, 95.0)
+            .xlim(0.0, 1.0)
+            .ylim(0.0, 3.0)
+            .plot(&[f64::NAN, f64::NAN], &[1.0, 2.0]);
+        assert_eq!(plot.resolved_axes().0.limits(), (0.0, 1.0));
+    }
+
+    #[test]
+    fn symlog_ticks_at_the_linear_edge_and_each_decade() {
+        let scale = Scale::SymLog { linthresh: 2.0 };
+        // Linear inside the threshold, and a unit per decade outside it
+        assert_eq!(scale.apply(1.0), 0.5);
+        assert_eq!(scale.apply(-2.0), -1.0);
+        assert!((scale.apply(20.0) - 2.0).abs() < 1e-12);
+        assert!((scale.invert(scale.apply(-300.0)) + 300.0).abs() < 1e-9);
+
+        let mut plot = Plot::new();
+        plot.yscale(scale)
+            .plot(&[0.0, 1.0, 2.0], &[-5.0, 0.5, 50.0]);
+        let (_, yaxis) = plot.resolved_axes();
+        let expected = [-20.0, -2.0, 0.0, 2.0, 20.0, 200.0];
+        assert_eq!(yaxis.ticks().len(), expected.len(), "{:?}", yaxis.ticks());
+        for (tick, expected) in yaxis.ticks().iter().zip(&expected) {
+            assert!((tick - expected).abs() < 1e-9, "{:?}", yaxis.ticks());
+        }
+        assert_eq!(yaxis.limits(), (-20.0, 200.0));
+    }
+
+    #[test]
+    fn hist2d_counts_points_and_leaves_empty_cells_white() {
+        let mut plot = Plot::new();
+        // The point at the maximum lands in the last bin rather than past it
+        plot.hist2d(&[0.0, 0.1, 0.9, 1.0], &[0.0, 0.2, 0.1, 1.0], 2, 2);
+        let color = |position: f64| {
+            let color = sample_colormap(plot.colormap.table(), position, false);
+            [
+                (color[0] * 255.0) as u8,
+                (color[1] * 255.0) as u8,
+                (color[2] * 255.0) as u8,
+            ]
+        };
+        let (high, low) = (color(255.0), color(0.0));
+        match &plot.items[0].1 {
+            Item::Image(image) => {
+                assert_eq!((image.width, image.height), (2, 2));
+                assert_eq!(image.extent, Some(((0.0, 1.0), (0.0, 1.0))));
+                // From the bottom left: two points, one, none, and one
+                let pixels = image.png_bytes.chunks(3).collect::<Vec<_>>();
+                assert_eq!(pixels, vec![&high[..], &low[..], &[255; 3][..], &low[..]]);
+            }
+            _ => panic!("hist2d should add an image"),
+        }
+    }
+
+    #[test]
+    fn hexbin_assigns_points_to_the_nearest_lattice_center() {
+        let mut plot = Plot::new();
+        // Two hexagons across and one row, so both lattices have a spacing of 2 in x and y
+        plot.hexbin(&[0.0, 4.0, 2.0, 2.1], &[0.0, 2.0, 1.0, 1.0], 2);
+        let (high, low) = (
+            rgb(plot.colorize(1.0, (0.0, 1.0))),
+            rgb(plot.colorize(0.0, (0.0, 1.0))),
+        );
+        match &plot.items[0].1 {
+            Item::Polygons { polygons, .. } => {
+                let cells = polygons
+                    .iter()
+                    .map(|(xs, ys, color)| {
+                        let mean =
+                            |values: &[f64]| (values.iter().sum::<f64>() / 6.0 * 1e9).round() / 1e9;
+                        let center = (mean(xs), mean(ys));
+                        (center, rgb(*color))
+                    })
+                    .collect::<Vec<_>>();
+                // The middle points are off the first lattice, so they share a cell on the second
+                assert_eq!(
+                    cells,
+                    vec![((0.0, 0.0), low), ((3.0, 1.0), high), ((4.0, 2.0), low)]
+                );
+            }
+            _ => panic!("hexbin should add polygons"),
+        }
+    }
+
+    #[test]
+    fn kde_uses_the_silverman_bandwidth() {
+        let mut plot = Plot::new();
+        // The standard deviation is √2, less than the IQR of 2 over 1.34, so it sets the spread
+        plot.kde(&[1.0, 2.0, 3.0, 4.0, 5.0, f64::NAN], None);
+        let bandwidth = 0.9 * 2f64.sqrt() * 5f64.powf(-0.2);
+        match &plot.items[0].1 {
+            Item::Series(series) => {
+                // The curve reaches three bandwidths past the extreme samples
+                assert!((series.x_values[0] - (1.0 - 3.0 * bandwidth)).abs() < 1e-12);
+                assert!((series.x_values[511] - (5.0 + 3.0 * bandwidth)).abs() < 1e-12);
+                // and is a density, so the area under it is close to 1
+                let area = series
+                    .x_values
+                    .windows(2)
+                    .zip(series.y_values.windows(2))
+                    .map(|(x, y)| (x[1] - x[0]) * (y[0] + y[1]) / 2.0)
+                    .sum::<f64>();
+                assert!((area - 1.0).abs() < 0.01, "area is {}", area);
+            }
+            _ => panic!("kde should add a series"),
+        }
+    }
+
+    #[test]
+    fn table_columns_fit_their_widest_entry_and_align_right() {
+        let mut plot = Plot::new();
+        plot.table(&["n", "mean"], &[vec![1.0, 2.5], vec![10000.0, f64::NAN]]);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+
+        let pad = plot.font_size * 0.4;
+        let widths = [
+            plot.width_of("10000.00").max(plot.width_in("n", true)) + 2.0 * pad,
+            plot.width_of("2.50").max(plot.width_in("mean", true)) + 2.0 * pad,
+        ];
+        let page = recording
+            .calls
+            .iter()
+            .find_map(|call| match call {
+                DrawCall::AddPage(size) => Some(size.width),
+                _ => None,
+            })
+            .expect("a table should add a page");
+        assert_eq!(page, widths[0] + widths[1] + 2.0 * plot.figure_padding);
+
+        let texts = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::DrawText {
+                    position,
+                    alignment,
+                    text,
+                    ..
+                } => {
+                    assert!(matches!(alignment, CenterRight));
+                    Some((text.as_str(), position.x))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Each entry ends one pad inside the right edge of its column, and NaN is left blank
+        let (first, second) = (widths[0] - pad, widths[0] + widths[1] - pad);
+        assert_eq!(
+            texts,
+            vec![
+                ("n", first),
+                ("mean", second),
+                ("1.00", first),
+                ("2.50", second),
+                ("10000.00", first),
+                ("", second),
+            ]
+        );
+    }
+
+    #[test]
+    fn caption_wraps_at_the_frame_width() {
+        let mut plot = Plot::new();
+        plot.caption("The  quick brown fox jumps over\tthe lazy dog, then the quick brown fox jumps over the lazy dog again")
+            .image(&[0.0; 21], 7, 3);
+        let frame = plot.compute_frame(&plot.items);
+        let lines = plot.caption_lines(frame.width);
+        // Runs of whitespace become single spaces, and no words are lost
+        assert_eq!(
+            lines.join(" "),
+            "The quick brown fox jumps over the lazy dog, then the quick brown fox jumps over the \
+             lazy dog again"
+        );
+        let max_width = frame.width - plot.font_size;
+        let width_of = |text: &str| plot.width_of(text) * 0.6;
+        for (line, next) in lines.iter().zip(&lines[1..]) {
+            // Each line fits unless it's a single long word, and the next word would not have
+            assert!(width_of(line) <= max_width || !line.contains(' '));
+            let next_word = next.split(' ').next().unwrap();
+            assert!(width_of(&format!("{} {}", line, next_word)) > max_width);
+        }
+
+        // Too narrow for any two words together, and wide enough for all of them
+        assert_eq!(plot.caption_lines(0.0).len(), 20);
+        assert_eq!(plot.caption_lines(f64::INFINITY).len(), 1);
+
+        // The drawn caption is the same lines, bottom line last
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        let texts = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::DrawText { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(texts.ends_with(&lines.iter().rev().cloned().collect::<Vec<_>>()));
+    }
+
+    #[test]
+    #[should_panic(expected = "x, re, and im must have the same length, got 3, 2, and 2")]
+    fn plot_complex_as_rejects_a_short_part() {
+        Plot::new().plot_complex_as(
+            &[0.0, 1.0, 2.0],
+            &[1.0, 0.0],
+            &[0.0, 1.0],
+            ComplexMode::Real,
+        );
+    }
+
+    #[test]
+    fn finish_figure_clears_the_data_and_keeps_the_labels() {
+        let mut plot = Plot::new();
+        plot.title("Runs")
+            .xlabel("time")
+            .caption("One figure of many")
+            .xlim(0.0, 2.0)
+            .table(&["n"], &[vec![1.0]])
+            .plot(&[0.0, 1.0], &[0.0, 1.0]);
+        let _ = plot.finish_figure();
+        assert!(plot.items.is_empty());
+        assert!(plot.table.is_none());
+        assert_eq!(plot.title.as_deref(), Some("Runs"));
+        assert_eq!(plot.xlabel.as_deref(), Some("time"));
+        assert_eq!(plot.caption.as_deref(), Some("One figure of many"));
+        assert_eq!(plot.xlim, Some((0.0, 2.0)));
+    }
+
+    #[test]
+    fn non_finite_values_leave_the_limits_finite() {
+        let x = [0.0, 1.0, f64::INFINITY, 3.0, f64::NAN];
+        let y = [0.0, f64::NAN, 2.0, 1.0, f64::NEG_INFINITY];
+        let (xaxis, yaxis) = Plot::new().digest_tick_settings(&[(&x[..], &y[..])]);
+        for &(min, max) in &[xaxis.limits, yaxis.limits] {
+            assert!(min.is_finite() && max.is_finite());
+        }
+        assert!(xaxis.limits.0 <= 0.0 && xaxis.limits.1 >= 3.0);
+        assert!(yaxis.limits.0 <= 0.0 && yaxis.limits.1 >= 1.0);
+
+        // With only one finite point left there's no range at all
+        let (xaxis, yaxis) =
+            Plot::new().digest_tick_settings(&[(&[2.0, f64::NAN][..], &[5.0, 1.0][..])]);
+        assert!(xaxis.limits.0 < 2.0 && 2.0 < xaxis.limits.1);
+        assert!(yaxis.limits.0 < 5.0 && 5.0 < yaxis.limits.1);
+    }
+
+    #[test]
+    fn infinity_leaves_a_gap() {
+        let mut plot = Plot::new();
+        plot.marker(Some(Marker::Dot)).plot(
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            &[0.0, 1.0, f64::INFINITY, 3.0, 4.0],
+        );
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+
+        // The ticks are all horizontal or vertical, and no segment of this data is
+        let runs = lines(&recording)
+            .into_iter()
+            .filter(|line| line.len() > 1 && line[0].x != line[1].x && line[0].y != line[1].y)
+            .collect::<Vec<_>>();
+        assert_eq!(runs.iter().map(|run| run.len()).collect::<Vec<_>>(), [2, 2]);
+        let markers = recording
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                DrawCall::FillPolygon(points) => Some(points),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(markers.len(), 4);
+        for point in runs.iter().chain(&markers).flat_map(|points| points.iter()) {
+            assert!(point.x.is_finite() && point.y.is_finite());
+        }
+    }
+
+    #[test]
+    fn zooming_into_one_period() {
+        use std::f64::consts::PI;
+        // The second period is ten times taller, so it would set the y limits if it were counted
+        let x = linspace(0.0, 4.0 * PI, 401);
+        let y = x
+            .iter()
+            .map(|&x| {
+                if x <= 2.0 * PI {
+                    x.sin()
+                } else {
+                    10.0 * x.sin()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut plot = Plot::new();
+        plot.xlim(0.0, 2.0 * PI).plot(&x, &y);
+        let (xaxis, yaxis) = plot.resolved_axes();
+
+        assert_eq!(xaxis.limits(), (0.0, 2.0 * PI));
+        let interval = xaxis.tick_interval();
+        assert!(!xaxis.ticks().is_empty());
+        for &tick in xaxis.ticks() {
+            assert!((0.0..=2.0 * PI).contains(&tick));
+            let steps = tick / interval;
+            assert!((steps - steps.round()).abs() < 1e-9);
+        }
+
+        let (bottom, top) = yaxis.limits();
+        assert!(bottom <= -1.0 && top >= 1.0);
+        assert!(bottom > -2.0 && top < 2.0);
+    }
+
+    #[test]
+    fn marker_lands_on_its_image_pixel() {
+        let mut plot = Plot::new();
+        plot.image(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0], 4, 2)
+            .marker(Some(Marker::Dot))
+            .plot(&[2.5], &[1.5]);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+
+        let (origin, size) = recording
+            .calls
+            .iter()
+            .find_map(|call| match call {
+                DrawCall::DrawImage { origin, size, .. } => Some((*origin, *size)),
+                _ => None,
+            })
+            .unwrap();
+        let marker = recording
+            .calls
+            .iter()
+            .find_map(|call| match call {
+                DrawCall::FillPolygon(points) => Some(points),
+                _ => None,
+            })
+            .unwrap();
+        let center_x = marker.iter().map(|p| p.x).sum::<f64>() / marker.len() as f64;
+        let center_y = marker.iter().map(|p| p.y).sum::<f64>() / marker.len() as f64;
+
+        // Pixel (2, 1) of a 4 by 2 image is centered at (2.5, 1.5)
+        assert!((center_x - (origin.x + size.width * 2.5 / 4.0)).abs() < 1e-6);
+        assert!((center_y - (origin.y + size.height * 1.5 / 2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn series_can_turn_off_the_plot_line() {
+        let mut plot = Plot::new();
+        plot.linestyle(Some(LineStyle::Dashed)).plot_as(
+            &[0.0, 1.0, 2.0],
+            &[0.0, 2.0, 1.0],
+            SeriesStyle {
+                linestyle: Some(LineStyle::None),
+                marker: Some(Marker::Square),
+                ..SeriesStyle::default()
+            },
+        );
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        // Only the border and ticks, which are straight across or up
+        for line in lines(&recording) {
+            assert!(line
+                .windows(2)
+                .all(|w| w[0].x == w[1].x || w[0].y == w[1].y));
+        }
+        let markers = recording
+            .calls
+            .iter()
+            .filter(|call| matches!(call, DrawCall::FillPolygon(_)))
+            .count();
+        assert_eq!(markers, 3);
+    }
+
+    #[test]
+    fn series_handle_survives_rendering() {
+        let mut plot = Plot::new();
+        let handle = plot.begin_series();
+        plot.push_point(&handle, 0.0, 0.0)
+            .push_point(&handle, 1.0, 1.0)
+            .axvspans(&[(0.2, 0.4)]);
+        // Spans are drawn before lines, which used to reorder the items under the handle
+        plot.render_to(&mut Recording::new());
+        plot.push_point(&handle, 2.0, 2.0);
+
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+        assert!(lines(&recording).iter().any(|line| line.len() == 3));
+    }
+
+    #[test]
+    fn polar_keeps_circles_in_x_and_y() {
+        let mut plot = Plot::new();
+        plot.polar().unit_circle();
+        match &plot.items[0].1 {
+            Item::Segments { segments, .. } => {
+                for &((x, y), _) in segments {
+                    assert!((x.hypot(y) - 1.0).abs() < 1e-9);
+                }
+            }
+            _ => panic!("unit_circle should add segments"),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "polar")]
+    fn polar_rejects_spans() {
+        Plot::new().polar().axvspans(&[(0.0, 1.0)]);
+    }
+
+    #[test]
+    fn phase_fold_drops_gaps_and_sorts() {
+        let x = [0.25, f64::NAN, 1.5, 2.75, 3.0];
+        let y = [1.0, 2.0, 3.0, f64::NAN, 5.0];
+        let (folded, columns) = phase_fold(&x, &[&y], 1.0, true);
+        assert_eq!(folded, vec![0.0, 0.25, 0.5, 1.0, 1.25, 1.5]);
+        assert_eq!(columns[0], vec![5.0, 1.0, 3.0, 5.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn phase_fold_applies_to_pushed_points() {
+        let mut plot = Plot::new();
+        plot.phase_fold(1.0, false);
+        let handle = plot.begin_series();
+        plot.push_point(&handle, 1.75, 1.0)
+            .push_point(&handle, 2.25, 2.0)
+            .push_point(&handle, f64::INFINITY, 3.0);
+        match &plot.items[0].1 {
+            Item::Series(series) => {
+                assert_eq!(series.x_values, vec![0.25, 0.75]);
+                assert_eq!(series.y_values, vec![2.0, 1.0]);
+            }
+            _ => panic!("begin_series should add a series"),
+        }
+    }
+
+    #[test]
+    fn break_x_keeps_one_tick_at_the_break() {
+        let mut plot = Plot::new();
+        plot.x_tick_interval(10.0)
+            .break_x(10.0, 90.0)
+            .plot(&[0.0, 5.0, 95.0, 100.0], &[0.0, 1.0, 2.0, 3.0]);
+        let (xaxis, _) = plot.resolved_axes();
+        assert_eq!(xaxis.ticks(), &[0.0, 10.0, 100.0]);
+    }
+
+    #[test]
+    fn hatch_applies_to_regions_added_after_it() {
+        let mut plot = Plot::new();
+        plot.axvspans(&[(0.0, 1.0)])
+            .hatch(Some(Hatch::Diagonal))
+            .axvspans(&[(2.0, 3.0)])
+            .hexbin(&[0.0, 1.0, 2.0], &[0.0, 1.0, 2.0], 4);
+        let hatches = plot
+            .items
+            .iter()
+            .map(|(_, item)| match item {
+                Item::VSpans { hatch, .. } | Item::Polygons { hatch, .. } => hatch.is_some(),
+                _ => panic!("only spans and hexbin cells were added"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(hatches, vec![false, true, false]);
+    }
+
+    #[test]
+    #[should_panic(expected = "finish_figure")]
+    fn series_handle_is_stale_after_finish_figure() {
+        let mut plot = Plot::new();
+        let handle = plot.begin_series();
+        plot.push_point(&handle, 0.0, 0.0)
+            .push_point(&handle, 1.0, 1.0);
+        let _ = plot.finish_figure();
+        plot.plot(&[0.0, 1.0], &[0.0, 1.0]);
+        plot.push_point(&handle, 1.0, 1.0);
     }
 }
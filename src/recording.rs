@@ -0,0 +1,195 @@
+use crate::backend::Backend;
+use pdfpdf::{Alignment, Color, Font, Point, Size};
+
+/// A single call made to a `Backend`
+#[derive(Clone, Debug)]
+pub enum DrawCall {
+    AddPage(Size<f64>),
+    Font(Font, f64),
+    SetColor(Color),
+    SetLineWidth(f64),
+    SetClippingBox(Point<f64>, Size<f64>),
+    DrawRectangle(Point<f64>, Size<f64>),
+    MoveTo(Point<f64>),
+    LineTo(Point<f64>),
+    EndLine,
+    DrawLine(Vec<Point<f64>>),
+    FillPolygon(Vec<Point<f64>>),
+    /// Unrotated text has 0 `degrees`
+    DrawText {
+        position: Point<f64>,
+        alignment: Alignment,
+        degrees: i32,
+        text: String,
+    },
+    Translate(f64, f64),
+    /// The pixels themselves aren't kept
+    DrawImage {
+        width: usize,
+        height: usize,
+        origin: Point<f64>,
+        size: Size<f64>,
+    },
+}
+
+/// A backend that keeps every call made to it, so that tests can check what a plot draws without
+/// reading PDF output
+///
+/// ```
+/// use pdfplot::{DrawCall, Plot, Recording};
+///
+/// let mut plot = Plot::new();
+/// plot.title("Title").plot(&[0.0, 1.0], &[0.0, 1.0]);
+/// let mut recording = Recording::new();
+/// plot.render_to(&mut recording);
+/// let mut texts = recording.calls.iter().filter_map(|c| match c {
+///     DrawCall::DrawText { text, .. } => Some(text),
+///     _ => None,
+/// });
+/// assert!(texts.any(|text| text == "Title"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    pub calls: Vec<DrawCall>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, call: DrawCall) -> &mut Self {
+        self.calls.push(call);
+        self
+    }
+}
+
+fn points<I, J>(x_values: I, y_values: J) -> Vec<Point<f64>>
+where
+    I: IntoIterator<Item = f64>,
+    J: IntoIterator<Item = f64>,
+{
+    x_values
+        .into_iter()
+        .zip(y_values)
+        .map(|(x, y)| Point { x, y })
+        .collect()
+}
+
+impl Backend for Recording {
+    fn add_page(&mut self, size: Size<f64>) -> &mut Self {
+        self.record(DrawCall::AddPage(size))
+    }
+
+    fn font(&mut self, font: Font, size: f64) -> &mut Self {
+        self.record(DrawCall::Font(font, size))
+    }
+
+    fn set_color(&mut self, color: Color) -> &mut Self {
+        self.record(DrawCall::SetColor(color))
+    }
+
+    fn set_line_width(&mut self, width: f64) -> &mut Self {
+        self.record(DrawCall::SetLineWidth(width))
+    }
+
+    fn set_clipping_box(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        self.record(DrawCall::SetClippingBox(origin, size))
+    }
+
+    fn draw_rectangle(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        self.record(DrawCall::DrawRectangle(origin, size))
+    }
+
+    fn move_to(&mut self, point: Point<f64>) -> &mut Self {
+        self.record(DrawCall::MoveTo(point))
+    }
+
+    fn line_to(&mut self, point: Point<f64>) -> &mut Self {
+        self.record(DrawCall::LineTo(point))
+    }
+
+    fn end_line(&mut self) -> &mut Self {
+        self.record(DrawCall::EndLine)
+    }
+
+    fn draw_line<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        self.record(DrawCall::DrawLine(points(x_values, y_values)))
+    }
+
+    fn fill_polygon<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        self.record(DrawCall::FillPolygon(points(x_values, y_values)))
+    }
+
+    fn draw_text(&mut self, position: Point<f64>, alignment: Alignment, text: &str) -> &mut Self {
+        self.draw_rotated_text(position, alignment, 0, text)
+    }
+
+    fn draw_rotated_text(
+        &mut self,
+        position: Point<f64>,
+        alignment: Alignment,
+        degrees: i32,
+        text: &str,
+    ) -> &mut Self {
+        self.record(DrawCall::DrawText {
+            position,
+            alignment,
+            degrees,
+            text: text.to_string(),
+        })
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> &mut Self {
+        self.record(DrawCall::Translate(x, y))
+    }
+
+    fn draw_image(
+        &mut self,
+        _rgb: &[u8],
+        width: usize,
+        height: usize,
+        origin: Point<f64>,
+        size: Size<f64>,
+    ) -> &mut Self {
+        self.record(DrawCall::DrawImage {
+            width,
+            height,
+            origin,
+            size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Plot;
+
+    #[test]
+    fn records_one_page_with_the_line_on_it() {
+        let mut plot = Plot::new();
+        plot.plot(&[0.0, 1.0, 2.0], &[0.0, 1.0, 0.0]);
+        let mut recording = Recording::new();
+        plot.render_to(&mut recording);
+
+        let pages = recording
+            .calls
+            .iter()
+            .filter(|call| matches!(call, DrawCall::AddPage(_)))
+            .count();
+        assert_eq!(pages, 1);
+        assert!(recording
+            .calls
+            .iter()
+            .any(|call| matches!(call, DrawCall::DrawLine(points) if points.len() == 3)));
+    }
+}
@@ -0,0 +1,431 @@
+use crate::backend::Backend;
+use pdfpdf::{Alignment, Color, Font, Point, Size};
+use std::fmt::Write;
+
+/// Renders a plot as an SVG document
+///
+/// SVG has no pages, so only the last page that was added ends up in the document.
+pub(crate) struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+    color: Color,
+    line_width: f64,
+    font: Font,
+    font_size: f64,
+    offset: (f64, f64),
+    path: String,
+    clip_paths: usize,
+}
+
+impl Svg {
+    pub(crate) fn new() -> Self {
+        Self {
+            width: 0.0,
+            height: 0.0,
+            body: String::new(),
+            color: Color::gray(0),
+            line_width: 1.0,
+            font: Font::Helvetica,
+            font_size: 12.0,
+            offset: (0.0, 0.0),
+            path: String::new(),
+            clip_paths: 0,
+        }
+    }
+
+    pub(crate) fn write_to<F>(&self, filename: F) -> std::io::Result<()>
+    where
+        F: AsRef<std::path::Path>,
+    {
+        let mut document = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\">\n",
+            w = self.width,
+            h = self.height
+        );
+        document.push_str(&self.body);
+        // Close the groups opened by each clipping box
+        for _ in 0..self.clip_paths {
+            document.push_str("</g>\n");
+        }
+        document.push_str("</svg>\n");
+        std::fs::write(filename, document)
+    }
+
+    // Flip into SVG's coordinates, which have the origin at the top left
+    fn x(&self, x: f64) -> f64 {
+        x + self.offset.0
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        self.height - (y + self.offset.1)
+    }
+
+    fn color(&self) -> String {
+        format!(
+            "rgb({},{},{})",
+            self.color.red, self.color.green, self.color.blue
+        )
+    }
+
+    fn stroke(&self) -> String {
+        format!(
+            "fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"",
+            self.color(),
+            self.line_width
+        )
+    }
+
+    fn points<I, J>(&self, x_values: I, y_values: J) -> String
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        let mut points = String::new();
+        for (x, y) in x_values.into_iter().zip(y_values) {
+            let _ = write!(points, "{},{} ", self.x(x), self.y(y));
+        }
+        points.trim_end().to_string()
+    }
+
+    fn text_attributes(&self, alignment: Alignment) -> String {
+        use Alignment::*;
+        let anchor = match alignment {
+            TopLeft | CenterLeft | BottomLeft => "start",
+            TopCenter | CenterCenter | BottomCenter => "middle",
+            TopRight | CenterRight | BottomRight => "end",
+        };
+        let baseline = match alignment {
+            TopLeft | TopCenter | TopRight => "text-before-edge",
+            CenterLeft | CenterCenter | CenterRight => "central",
+            BottomLeft | BottomCenter | BottomRight => "auto",
+        };
+        // Plots only ever set Helvetica and its bold weight
+        let weight = match self.font {
+            Font::HelveticaBold => "bold",
+            _ => "normal",
+        };
+        format!(
+            "font-family=\"Helvetica, Arial, sans-serif\" font-weight=\"{}\" font-size=\"{}\" \
+             text-anchor=\"{}\" dominant-baseline=\"{}\" fill=\"{}\"",
+            weight,
+            self.font_size,
+            anchor,
+            baseline,
+            self.color()
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Backend for Svg {
+    fn add_page(&mut self, size: Size<f64>) -> &mut Self {
+        self.width = size.width;
+        self.height = size.height;
+        self.body.clear();
+        self.offset = (0.0, 0.0);
+        self.clip_paths = 0;
+        self
+    }
+
+    fn font(&mut self, font: Font, size: f64) -> &mut Self {
+        self.font = font;
+        self.font_size = size;
+        self
+    }
+
+    fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    fn set_line_width(&mut self, width: f64) -> &mut Self {
+        self.line_width = width;
+        self
+    }
+
+    fn set_clipping_box(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        // Clipping applies until the end of the page, so leave the group open
+        let _ = writeln!(
+            self.body,
+            "<clipPath id=\"clip{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\
+             </clipPath>\n<g clip-path=\"url(#clip{})\">",
+            self.clip_paths,
+            self.x(origin.x),
+            self.y(origin.y + size.height),
+            size.width,
+            size.height,
+            self.clip_paths
+        );
+        self.clip_paths += 1;
+        self
+    }
+
+    fn draw_rectangle(&mut self, origin: Point<f64>, size: Size<f64>) -> &mut Self {
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+            self.x(origin.x),
+            self.y(origin.y + size.height),
+            size.width,
+            size.height,
+            self.stroke()
+        );
+        self
+    }
+
+    fn move_to(&mut self, point: Point<f64>) -> &mut Self {
+        let _ = write!(self.path, "M{} {} ", self.x(point.x), self.y(point.y));
+        self
+    }
+
+    fn line_to(&mut self, point: Point<f64>) -> &mut Self {
+        let _ = write!(self.path, "L{} {} ", self.x(point.x), self.y(point.y));
+        self
+    }
+
+    fn end_line(&mut self) -> &mut Self {
+        let path = std::mem::take(&mut self.path);
+        let _ = writeln!(
+            self.body,
+            "<path d=\"{}\" {}/>",
+            path.trim_end(),
+            self.stroke()
+        );
+        self
+    }
+
+    fn draw_line<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        let points = self.points(x_values, y_values);
+        let _ = writeln!(
+            self.body,
+            "<polyline points=\"{}\" {}/>",
+            points,
+            self.stroke()
+        );
+        self
+    }
+
+    fn fill_polygon<I, J>(&mut self, x_values: I, y_values: J) -> &mut Self
+    where
+        I: IntoIterator<Item = f64>,
+        J: IntoIterator<Item = f64>,
+    {
+        let points = self.points(x_values, y_values);
+        let _ = writeln!(
+            self.body,
+            "<polygon points=\"{}\" fill=\"{}\"/>",
+            points,
+            self.color()
+        );
+        self
+    }
+
+    fn draw_text(&mut self, position: Point<f64>, alignment: Alignment, text: &str) -> &mut Self {
+        self.draw_rotated_text(position, alignment, 0, text)
+    }
+
+    fn draw_rotated_text(
+        &mut self,
+        position: Point<f64>,
+        alignment: Alignment,
+        degrees: i32,
+        text: &str,
+    ) -> &mut Self {
+        let (x, y) = (self.x(position.x), self.y(position.y));
+        // SVG's y axis points down, so its rotations are clockwise
+        let transform = if degrees == 0 {
+            String::new()
+        } else {
+            format!(" transform=\"rotate({} {} {})\"", -degrees, x, y)
+        };
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{}\" y=\"{}\" {}{}>{}</text>",
+            x,
+            y,
+            self.text_attributes(alignment),
+            transform,
+            escape(text)
+        );
+        self
+    }
+
+    fn translate(&mut self, x: f64, y: f64) -> &mut Self {
+        self.offset.0 += x;
+        self.offset.1 += y;
+        self
+    }
+
+    fn draw_image(
+        &mut self,
+        rgb: &[u8],
+        width: usize,
+        height: usize,
+        origin: Point<f64>,
+        size: Size<f64>,
+    ) -> &mut Self {
+        let _ = writeln!(
+            self.body,
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" \
+             style=\"image-rendering:pixelated\" href=\"data:image/png;base64,{}\"/>",
+            self.x(origin.x),
+            self.y(origin.y + size.height),
+            size.width,
+            size.height,
+            base64(&png(rgb, width, height))
+        );
+        self
+    }
+}
+
+/// Encode RGB pixels as an uncompressed PNG, flipping the rows so that the first is at the bottom
+fn png(rgb: &[u8], width: usize, height: usize) -> Vec<u8> {
+    // Each row starts with a filter type byte, 0 for none
+    let mut raw = Vec::with_capacity((width * 3 + 1) * height);
+    for row in rgb.chunks(width * 3).rev() {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // A zlib stream made of stored deflate blocks, which hold at most 65535 bytes each
+    let mut zlib = vec![0x78, 0x01];
+    let blocks = raw.chunks(65535).collect::<Vec<_>>();
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push((i == blocks.len() - 1) as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    if blocks.is_empty() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bits per channel, RGB, and the only compression, filtering, and interlacing methods
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    for (kind, data) in &[(b"IHDR", header), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(*kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    png
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn base64_round_trips() {
+        fn decode(text: &str) -> Vec<u8> {
+            const ALPHABET: &[u8; 64] =
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+            let sextets = text
+                .bytes()
+                .filter(|&c| c != b'=')
+                .map(|c| ALPHABET.iter().position(|&a| a == c).unwrap() as u32)
+                .collect::<Vec<_>>();
+            let mut bytes = Vec::new();
+            for chunk in sextets.chunks(4) {
+                let n = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |n, (i, &s)| n | s << (18 - 6 * i));
+                bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+            }
+            bytes
+        }
+
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b""), "");
+        for len in 0..10 {
+            let bytes = (0..len).map(|i| (i * 37 + 200) as u8).collect::<Vec<_>>();
+            assert_eq!(decode(&base64(&bytes)), bytes);
+        }
+    }
+
+    #[test]
+    fn png_checksums_its_chunks() {
+        let png = png(&[255, 0, 0, 0, 0, 255], 1, 2);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        // IHDR is 13 bytes long and its checksum covers its type and data
+        assert_eq!(&png[8..16], b"\0\0\0\x0dIHDR");
+        let crc = u32::from_be_bytes([png[29], png[30], png[31], png[32]]);
+        assert_eq!(crc, crc32(&png[12..29]));
+        // The file always ends with the empty IEND chunk
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xaeB`\x82");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 pub fn loadtxt(filename: &str) -> Vec<Vec<f64>> {
     let mut columns = Vec::new();
     for line in std::fs::read_to_string(filename).unwrap().lines() {
@@ -12,6 +14,174 @@ pub fn loadtxt(filename: &str) -> Vec<Vec<f64>> {
     columns
 }
 
+/// Write columns as whitespace-separated rows that `loadtxt` reads back exactly
+///
+/// Columns shorter than the longest are padded with NaN.
+pub fn savetxt(columns: &[Vec<f64>], filename: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(filename)?);
+    for r in 0..rows {
+        let row = columns
+            .iter()
+            .map(|column| column.get(r).cloned().unwrap_or(f64::NAN).to_string())
+            .collect::<Vec<_>>();
+        writeln!(file, "{}", row.join(" "))?;
+    }
+    file.flush()
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    /// The file has no header line
+    NoHeader,
+    /// A column name appears more than once in the header
+    DuplicateHeader(String),
+    /// A row has a different number of values than the header has names
+    WrongLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A value could not be parsed as a number
+    Parse {
+        line: usize,
+        word: String,
+    },
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::NoHeader => write!(f, "no header line"),
+            LoadError::DuplicateHeader(name) => write!(f, "duplicate column name {:?}", name),
+            LoadError::WrongLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} values but the header names {} columns",
+                line, found, expected
+            ),
+            LoadError::Parse { line, word } => {
+                write!(f, "line {}: {:?} is not a number", line, word)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+/// Load whitespace-delimited columns keyed by the names in the first line of the file
+///
+/// Blank lines and lines starting with `#` are skipped.
+pub fn loadtxt_named(filename: &str) -> Result<HashMap<String, Vec<f64>>, LoadError> {
+    let contents = std::fs::read_to_string(filename)?;
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(l, line)| (l + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let headers = lines
+        .next()
+        .ok_or(LoadError::NoHeader)?
+        .1
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    let mut columns = vec![Vec::new(); headers.len()];
+
+    for (l, line) in lines {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.len() != headers.len() {
+            return Err(LoadError::WrongLength {
+                line: l,
+                expected: headers.len(),
+                found: words.len(),
+            });
+        }
+        for (column, word) in columns.iter_mut().zip(words) {
+            column.push(word.parse::<f64>().map_err(|_| LoadError::Parse {
+                line: l,
+                word: word.to_string(),
+            })?);
+        }
+    }
+
+    let mut named = HashMap::new();
+    for (name, column) in headers.into_iter().zip(columns) {
+        if named.insert(name.to_string(), column).is_some() {
+            return Err(LoadError::DuplicateHeader(name.to_string()));
+        }
+    }
+    Ok(named)
+}
+
+/// `n` evenly spaced values from `start` to `end`, inclusive; none for `n == 0` and just `start`
+/// for `n == 1`
+pub fn linspace(start: f64, end: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![start],
+        _ => {}
+    }
+    let step = (end - start) / (n - 1) as f64;
+    (0..n).map(|i| start + i as f64 * step).collect()
+}
+
+/// `n` values from `10^start_exp` to `10^end_exp`, inclusive, evenly spaced in their logarithm
+pub fn logspace(start_exp: f64, end_exp: f64, n: usize) -> Vec<f64> {
+    linspace(start_exp, end_exp, n)
+        .into_iter()
+        .map(|e| 10.0f64.powf(e))
+        .collect()
+}
+
+/// Linearly interpolated `p`th percentile (0 to 100) of the finite values, if there are any
+pub(crate) fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let mut sorted = values
+        .iter()
+        .cloned()
+        .filter(|v| v.is_finite())
+        .collect::<Vec<_>>();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let rank = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let below = sorted[rank.floor() as usize];
+    let above = sorted[rank.ceil() as usize];
+    Some(below + (above - below) * rank.fract())
+}
+
+/// A small xorshift generator, so that random placement is reproducible without a dependency
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // xorshift gets stuck at 0
+        Rng(seed.max(1))
+    }
+
+    /// Uniformly distributed in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
 pub(crate) trait ToU64 {
     fn to_u64(self) -> u64;
 }
@@ -25,7 +195,7 @@ impl ToU64 for f64 {
             u64::min_value()
         );
         assert!(
-            self <= u64::max_value() as f64
+            self <= u64::max_value() as f64,
             "{} > u64::max_value(), {}",
             self,
             u64::max_value()
@@ -52,3 +222,98 @@ where
         max
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A file in the temporary directory that's unique to this test process
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("pdfplot-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn savetxt_round_trips_through_loadtxt() {
+        let columns = vec![
+            vec![0.1, 1.0 / 3.0, -2.5e-300, 1e300],
+            vec![f64::MAX, f64::MIN_POSITIVE, -0.0],
+        ];
+        let path = temp_file("saved.txt", "");
+        savetxt(&columns, &path).unwrap();
+        let loaded = loadtxt(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0], columns[0]);
+        // The shorter column comes back padded with NaN
+        assert_eq!(loaded[1][..3], columns[1][..]);
+        assert!(loaded[1][3].is_nan());
+    }
+
+    #[test]
+    fn loadtxt_named_reads_columns_by_name() {
+        let path = temp_file("named.txt", "# a comment\n\nt v\n0 1.5\n1 -2\n");
+        let columns = loadtxt_named(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(columns["t"], vec![0.0, 1.0]);
+        assert_eq!(columns["v"], vec![1.5, -2.0]);
+    }
+
+    #[test]
+    fn loadtxt_named_reports_bad_files() {
+        let load = |name: &str, contents: &str| {
+            let path = temp_file(name, contents);
+            let result = loadtxt_named(&path);
+            let _ = std::fs::remove_file(&path);
+            result.unwrap_err()
+        };
+        assert!(matches!(
+            load("empty.txt", "# only a comment\n"),
+            LoadError::NoHeader
+        ));
+        assert!(matches!(
+            load("ragged.txt", "t v\n0 1\n1\n"),
+            LoadError::WrongLength {
+                line: 3,
+                expected: 2,
+                found: 1
+            }
+        ));
+        match load("word.txt", "t v\n0 one\n") {
+            LoadError::Parse { line, word } => {
+                assert_eq!(line, 2);
+                assert_eq!(word, "one");
+            }
+            e => panic!("expected a parse error, got {}", e),
+        }
+        match load("duplicate.txt", "t v t\n0 1 2\n") {
+            LoadError::DuplicateHeader(name) => assert_eq!(name, "t"),
+            e => panic!("expected a duplicate header, got {}", e),
+        }
+        let missing = std::env::temp_dir().join("pdfplot-no-such-file.txt");
+        assert!(matches!(
+            loadtxt_named(missing.to_str().unwrap()),
+            Err(LoadError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn linspace_counts() {
+        assert!(linspace(0.0, 1.0, 0).is_empty());
+        assert!(logspace(0.0, 1.0, 0).is_empty());
+        assert_eq!(linspace(2.0, 3.0, 1), vec![2.0]);
+        assert_eq!(linspace(0.0, 1.0, 3), vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn percentile_interpolates_the_finite_values() {
+        let values = [3.0, f64::NAN, 1.0, 2.0, f64::INFINITY, 4.0];
+        assert_eq!(percentile(&values, 0.0), Some(1.0));
+        assert_eq!(percentile(&values, 50.0), Some(2.5));
+        assert_eq!(percentile(&values, 100.0), Some(4.0));
+        assert_eq!(percentile(&[f64::NAN, f64::INFINITY], 50.0), None);
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+}